@@ -5,8 +5,9 @@ use {
 		Result,
 	},
 	elastic_scraper::elastic::ElasticRecord,
-	schnosedb::models::{MapRow, ServerRow},
+	schnosedb::models::{MapRow, PlayerNameRow, RecordStatus, ServerRow},
 	sqlx::{MySql, Pool},
+	std::collections::HashMap,
 	tracing::info,
 };
 
@@ -16,6 +17,7 @@ pub async fn parse(
 	args: &Args,
 ) -> Result<()> {
 	let mut records = Vec::new();
+	let mut player_names = HashMap::<(u32, String), PlayerNameRow>::new();
 
 	let map_ids: Vec<MapRow> = sqlx::query_as("SELECT * FROM maps")
 		.fetch_all(database_connection)
@@ -59,6 +61,19 @@ pub async fn parse(
 			})
 			.unwrap_or(0);
 
+		player_names
+			.entry((record.steam_id.as_id32(), record.player_name.clone()))
+			.and_modify(|name| {
+				name.first_seen = name.first_seen.min(record.created_on);
+				name.last_seen = name.last_seen.max(record.created_on);
+			})
+			.or_insert_with(|| PlayerNameRow {
+				player_id: record.steam_id.as_id32(),
+				name: record.player_name.clone(),
+				first_seen: record.created_on,
+				last_seen: record.created_on,
+			});
+
 		let record = schnosedb::models::RecordRow {
 			id: record.id,
 			course_id: (map_id as u32 * 1000) + record.stage as u32,
@@ -70,7 +85,11 @@ pub async fn parse(
 				.teleports
 				.try_into()
 				.context("Teleports exceeded u16::MAX")?,
+			tickrate: Some(record.tickrate),
 			created_on: record.created_on,
+			status: RecordStatus::Valid,
+			status_reason: None,
+			moderated_by: None,
 		};
 
 		records.push(record);
@@ -78,10 +97,28 @@ pub async fn parse(
 	}
 
 	let bytes = serde_json::to_vec(&records).context("Failed to serialize records.")?;
-	if args.output_path.is_file() {
-		std::fs::write(&args.output_path, bytes).context("Failed to write JSON to disk.")
+	let names_bytes = serde_json::to_vec(
+		&player_names
+			.into_values()
+			.collect::<Vec<_>>(),
+	)
+	.context("Failed to serialize player names.")?;
+
+	// Player names always go next to the records so they can be inserted separately.
+	let (records_path, names_path) = if args.output_path.is_file() {
+		(
+			args.output_path.clone(),
+			args.output_path
+				.with_file_name("player_names.json"),
+		)
 	} else {
-		std::fs::write(args.output_path.join("records.json"), bytes)
-			.context("Failed to write JSON to disk.")
-	}
+		(
+			args.output_path.join("records.json"),
+			args.output_path
+				.join("player_names.json"),
+		)
+	};
+
+	std::fs::write(records_path, bytes).context("Failed to write JSON to disk.")?;
+	std::fs::write(names_path, names_bytes).context("Failed to write JSON to disk.")
 }
@@ -2,6 +2,7 @@ use {
 	crate::Args,
 	color_eyre::{eyre::Context, Result},
 	gokz_rs::global_api::Record,
+	schnosedb::models::RecordStatus,
 	sqlx::types::chrono::{DateTime, Utc},
 };
 
@@ -20,7 +21,11 @@ pub fn parse(elastic_records: Vec<Record>, args: &Args) -> Result<()> {
 				.teleports
 				.try_into()
 				.context("Teleports exceeded u16::MAX")?,
+			tickrate: Some(record.tickrate),
 			created_on: DateTime::<Utc>::from_utc(record.created_on, Utc),
+			status: RecordStatus::Valid,
+			status_reason: None,
+			moderated_by: None,
 		};
 
 		records.push(record);
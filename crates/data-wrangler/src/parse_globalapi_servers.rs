@@ -6,8 +6,6 @@ use {
 	serde::{Deserialize, Deserializer},
 };
 
-const ZPAMM: u32 = 17690692;
-
 #[derive(Debug, Deserialize)]
 pub struct Server {
 	pub id: u16,
@@ -18,6 +16,10 @@ pub struct Server {
 	#[serde(deserialize_with = "deserialize_unknown")]
 	pub ip: String,
 	pub port: u16,
+	#[serde(default)]
+	pub tickrate: Option<u8>,
+	#[serde(default, alias = "approved_by_steamid64", deserialize_with = "deserialize_steam_id")]
+	pub approved_by: u32,
 }
 
 fn deserialize_unknown<'de, D: Deserializer<'de>>(
@@ -55,8 +57,11 @@ pub fn parse(servers: Vec<Server>, args: &Args) -> Result<()> {
 		.map(|server| ServerRow {
 			id: server.id,
 			name: server.name,
+			ip: (server.ip != "unknown").then_some(server.ip),
+			port: (server.port != 0).then_some(server.port),
+			tickrate: server.tickrate,
 			owned_by: server.owner_steamid,
-			approved_by: ZPAMM,
+			approved_by: server.approved_by,
 		})
 		.collect::<Vec<_>>();
 
@@ -0,0 +1,355 @@
+//! A minimal client for the Source engine's A2S_INFO and A2S_PLAYER queries.
+//!
+//! See <https://developer.valvesoftware.com/wiki/Server_queries>. Split (multi-packet) responses
+//! are not supported, which is fine for the small player counts of KZ servers.
+
+use {
+	std::{io, net::SocketAddr, time::Duration},
+	tokio::{net::UdpSocket, time::timeout},
+};
+
+const HEADER: [u8; 4] = [0xFF; 4];
+const A2S_INFO: u8 = b'T';
+const A2S_INFO_RESPONSE: u8 = b'I';
+const A2S_PLAYER: u8 = b'U';
+const A2S_PLAYER_RESPONSE: u8 = b'D';
+const CHALLENGE_RESPONSE: u8 = b'A';
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Info {
+	pub name: String,
+	pub map: String,
+	pub players: u8,
+	pub max_players: u8,
+	pub bots: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Player {
+	pub name: String,
+	pub score: i32,
+
+	/// Seconds since the player connected.
+	pub duration: f32,
+}
+
+/// Asks the server at `addr` for its name, map and player counts.
+pub async fn info(addr: SocketAddr, max_wait: Duration) -> io::Result<Info> {
+	let mut request = request(A2S_INFO);
+	request.extend_from_slice(b"Source Engine Query\0");
+
+	let response = query(addr, request, A2S_INFO_RESPONSE, max_wait).await?;
+	let mut reader = Reader(&response);
+
+	let _protocol = reader.u8()?;
+	let name = reader.string()?;
+	let map = reader.string()?;
+	let _folder = reader.string()?;
+	let _game = reader.string()?;
+	let _app_id = reader.bytes(2)?;
+	let players = reader.u8()?;
+	let max_players = reader.u8()?;
+	let bots = reader.u8()?;
+
+	Ok(Info { name, map, players, max_players, bots })
+}
+
+/// Asks the server at `addr` for everyone currently connected.
+pub async fn players(addr: SocketAddr, max_wait: Duration) -> io::Result<Vec<Player>> {
+	// No challenge yet; the server will hand us one.
+	let mut request = request(A2S_PLAYER);
+	request.extend_from_slice(&[0xFF; 4]);
+
+	let response = query(addr, request, A2S_PLAYER_RESPONSE, max_wait).await?;
+	let mut reader = Reader(&response);
+
+	let count = reader.u8()?;
+	let mut players = Vec::with_capacity(count as usize);
+
+	for _ in 0..count {
+		let _index = reader.u8()?;
+
+		players.push(Player {
+			name: reader.string()?,
+			score: i32::from_le_bytes(reader.array()?),
+			duration: f32::from_le_bytes(reader.array()?),
+		});
+	}
+
+	Ok(players)
+}
+
+fn request(kind: u8) -> Vec<u8> {
+	let mut request = HEADER.to_vec();
+	request.push(kind);
+	request
+}
+
+/// Sends `request` and returns the payload of the response of type `expected`. If the server
+/// answers with a challenge instead, the request is sent again with the challenge attached.
+async fn query(
+	addr: SocketAddr,
+	mut request: Vec<u8>,
+	expected: u8,
+	max_wait: Duration,
+) -> io::Result<Vec<u8>> {
+	let bind_addr = match addr {
+		SocketAddr::V4(_) => "0.0.0.0:0",
+		SocketAddr::V6(_) => "[::]:0",
+	};
+
+	let socket = UdpSocket::bind(bind_addr).await?;
+	socket.connect(addr).await?;
+
+	// One retry for the challenge is all the protocol needs.
+	for _ in 0..2 {
+		socket.send(&request).await?;
+
+		let mut buf = [0; 1400];
+		let len = timeout(max_wait, socket.recv(&mut buf))
+			.await
+			.map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "A2S query timed out"))??;
+
+		let Some(payload) = buf[..len].strip_prefix(&HEADER) else {
+			return Err(invalid_data("unexpected packet header"));
+		};
+
+		match payload.split_first() {
+			Some((&kind, payload)) if kind == expected => return Ok(payload.to_vec()),
+			Some((&CHALLENGE_RESPONSE, challenge)) if challenge.len() == 4 => {
+				// A2S_INFO appends the challenge, A2S_PLAYER replaces its placeholder.
+				if expected == A2S_PLAYER_RESPONSE {
+					request.truncate(HEADER.len() + 1);
+				}
+
+				request.extend_from_slice(challenge);
+			}
+			_ => return Err(invalid_data("unexpected response type")),
+		}
+	}
+
+	Err(invalid_data("server kept sending challenges"))
+}
+
+fn invalid_data(message: &'static str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+	fn bytes(&mut self, len: usize) -> io::Result<&'a [u8]> {
+		if self.0.len() < len {
+			return Err(invalid_data("response ended early"));
+		}
+
+		let (bytes, rest) = self.0.split_at(len);
+		self.0 = rest;
+
+		Ok(bytes)
+	}
+
+	fn array<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+		Ok(self
+			.bytes(N)?
+			.try_into()
+			.expect("`bytes` returns exactly `N` bytes"))
+	}
+
+	fn u8(&mut self) -> io::Result<u8> {
+		Ok(self.bytes(1)?[0])
+	}
+
+	/// Null-terminated, and not necessarily valid UTF-8.
+	fn string(&mut self) -> io::Result<String> {
+		let len = self
+			.0
+			.iter()
+			.position(|&byte| byte == 0)
+			.ok_or_else(|| invalid_data("unterminated string"))?;
+
+		let string = String::from_utf8_lossy(self.bytes(len)?).into_owned();
+		self.bytes(1)?;
+
+		Ok(string)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, std::net::Ipv4Addr};
+
+	const CHALLENGE: [u8; 4] = [0x12, 0x34, 0x56, 0x78];
+	const MAX_WAIT: Duration = Duration::from_secs(5);
+
+	/// A fake game server on a random local port. Requests without [`CHALLENGE`] are answered with
+	/// a challenge, everything else is answered by `respond`.
+	async fn serve(respond: fn(&[u8]) -> Vec<u8>) -> SocketAddr {
+		let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+			.await
+			.unwrap();
+		let addr = socket.local_addr().unwrap();
+
+		tokio::spawn(async move {
+			let mut buf = [0; 1400];
+
+			while let Ok((len, client)) = socket.recv_from(&mut buf).await {
+				let request = &buf[..len];
+
+				let response = if request.ends_with(&CHALLENGE) {
+					respond(request)
+				} else {
+					[
+						&HEADER[..],
+						&[CHALLENGE_RESPONSE],
+						&CHALLENGE,
+					]
+					.concat()
+				};
+
+				socket
+					.send_to(&response, client)
+					.await
+					.unwrap();
+			}
+		});
+
+		addr
+	}
+
+	fn info_response(received: &[u8]) -> Vec<u8> {
+		assert_eq!(
+			received,
+			[
+				&HEADER[..],
+				b"TSource Engine Query\0",
+				&CHALLENGE
+			]
+			.concat()
+		);
+
+		let mut response = request(A2S_INFO_RESPONSE);
+		response.push(17);
+		response.extend_from_slice(b"KZ Server\0kz_beginnerblock_go\0csgo\0Counter-Strike\0");
+		response.extend_from_slice(&730_u16.to_le_bytes());
+		response.extend_from_slice(&[3, 64, 1]);
+		response
+	}
+
+	fn players_response(received: &[u8]) -> Vec<u8> {
+		// The placeholder is replaced, not followed, by the challenge.
+		assert_eq!(received, [&HEADER[..], b"U", &CHALLENGE].concat());
+
+		let mut response = request(A2S_PLAYER_RESPONSE);
+		response.push(2);
+
+		for (name, score, duration) in [
+			(&b"AlphaKeks"[..], 3, 90.5_f32),
+			(b"\xFFbot", -1, 0.0),
+		] {
+			response.push(0);
+			response.extend_from_slice(name);
+			response.push(0);
+			response.extend_from_slice(&i32::to_le_bytes(score));
+			response.extend_from_slice(&duration.to_le_bytes());
+		}
+
+		response
+	}
+
+	#[tokio::test]
+	async fn info_after_challenge() {
+		let addr = serve(info_response).await;
+
+		assert_eq!(
+			info(addr, MAX_WAIT).await.unwrap(),
+			Info {
+				name: String::from("KZ Server"),
+				map: String::from("kz_beginnerblock_go"),
+				players: 3,
+				max_players: 64,
+				bots: 1,
+			}
+		);
+	}
+
+	#[tokio::test]
+	async fn players_after_challenge() {
+		let addr = serve(players_response).await;
+
+		assert_eq!(
+			players(addr, MAX_WAIT).await.unwrap(),
+			vec![
+				Player {
+					name: String::from("AlphaKeks"),
+					score: 3,
+					duration: 90.5
+				},
+				Player {
+					name: String::from("\u{FFFD}bot"),
+					score: -1,
+					duration: 0.0
+				},
+			]
+		);
+	}
+
+	#[tokio::test]
+	async fn truncated_info() {
+		let addr = serve(|request| {
+			let mut response = info_response(request);
+			response.truncate(response.len() - 2);
+			response
+		})
+		.await;
+
+		let error = info(addr, MAX_WAIT).await.unwrap_err();
+
+		assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+		assert_eq!(error.to_string(), "response ended early");
+	}
+
+	#[tokio::test]
+	async fn unexpected_response_type() {
+		let addr = serve(|_| request(A2S_INFO_RESPONSE)).await;
+
+		let error = players(addr, MAX_WAIT)
+			.await
+			.unwrap_err();
+
+		assert_eq!(error.to_string(), "unexpected response type");
+	}
+
+	#[test]
+	fn reader() {
+		let mut reader = Reader(b"\x07map\0\x01\x00\x00\x00");
+
+		assert_eq!(reader.u8().unwrap(), 7);
+		assert_eq!(reader.string().unwrap(), "map");
+		assert_eq!(i32::from_le_bytes(reader.array().unwrap()), 1);
+		assert!(reader.0.is_empty());
+	}
+
+	#[test]
+	fn reader_truncated() {
+		let mut reader = Reader(&[1, 2, 3]);
+
+		let error = reader.array::<4>().unwrap_err();
+
+		assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+		assert_eq!(error.to_string(), "response ended early");
+
+		// Nothing is consumed on failure.
+		assert_eq!(reader.0, [1, 2, 3]);
+	}
+
+	#[test]
+	fn reader_unterminated_string() {
+		let mut reader = Reader(b"kz_");
+
+		let error = reader.string().unwrap_err();
+
+		assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+		assert_eq!(error.to_string(), "unterminated string");
+	}
+}
@@ -0,0 +1,90 @@
+use {
+	crate::state::APIState,
+	axum::{
+		async_trait,
+		extract::FromRequestParts,
+		http::{header, request::Parts},
+	},
+	rand::{distributions::Alphanumeric, Rng},
+	schnose_api::error::Error,
+	sha2::{Digest, Sha256},
+	subtle::ConstantTimeEq,
+};
+
+/// Guards routes that modify data. Requests need to send `Authorization: Bearer <ADMIN_KEY>`.
+/// Without an `ADMIN_KEY` secret every request is rejected.
+#[derive(Debug, Clone, Copy)]
+pub struct Admin;
+
+#[async_trait]
+impl FromRequestParts<APIState> for Admin {
+	type Rejection = Error;
+
+	async fn from_request_parts(
+		parts: &mut Parts,
+		state: &APIState,
+	) -> Result<Self, Self::Rejection> {
+		let is_admin = match (bearer_token(parts), state.admin_key.as_deref()) {
+			(Some(token), Some(admin_key)) => bool::from(
+				token
+					.as_bytes()
+					.ct_eq(admin_key.as_bytes()),
+			),
+			_ => false,
+		};
+
+		match is_admin {
+			true => Ok(Self),
+			false => Err(Error::Unauthorized),
+		}
+	}
+}
+
+/// Guards routes for API key holders. Requests need to send `Authorization: Bearer <key>` with a
+/// key issued via `POST /api/keys`.
+#[derive(Debug, Clone, Copy)]
+pub struct ApiKey {
+	pub id: u32,
+}
+
+#[async_trait]
+impl FromRequestParts<APIState> for ApiKey {
+	type Rejection = Error;
+
+	async fn from_request_parts(
+		parts: &mut Parts,
+		state: &APIState,
+	) -> Result<Self, Self::Rejection> {
+		let token = bearer_token(parts).ok_or(Error::Unauthorized)?;
+
+		let (id,): (u32,) = sqlx::query_as("SELECT id FROM api_keys WHERE key_hash = ?")
+			.bind(hash_key(token))
+			.fetch_optional(state.db())
+			.await?
+			.ok_or(Error::Unauthorized)?;
+
+		Ok(Self { id })
+	}
+}
+
+fn bearer_token(parts: &Parts) -> Option<&str> {
+	parts
+		.headers
+		.get(header::AUTHORIZATION)
+		.and_then(|value| value.to_str().ok())
+		.and_then(|value| value.strip_prefix("Bearer "))
+}
+
+/// API keys are only ever stored as their SHA-256 hash.
+pub fn hash_key(key: &str) -> String {
+	format!("{:x}", Sha256::digest(key.as_bytes()))
+}
+
+/// Random alphanumeric string used for API keys and webhook secrets.
+pub fn generate_secret() -> String {
+	rand::thread_rng()
+		.sample_iter(&Alphanumeric)
+		.take(48)
+		.map(char::from)
+		.collect()
+}
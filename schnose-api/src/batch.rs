@@ -0,0 +1,17 @@
+use schnose_api::error::{yeet, Error, Result};
+
+/// The most identifiers a single batch request (or `?ids=` list) may contain.
+pub const MAX_SIZE: usize = 100;
+
+/// Rejects batches that are empty or larger than [`MAX_SIZE`].
+pub fn check_size(len: usize) -> Result<()> {
+	if len == 0 {
+		yeet!(Error::InvalidInput("Batches must contain at least one identifier."));
+	}
+
+	if len > MAX_SIZE {
+		yeet!(Error::InvalidInput("Batches may contain at most 100 identifiers."));
+	}
+
+	Ok(())
+}
@@ -0,0 +1,160 @@
+use {
+	itertools::Itertools,
+	std::{
+		any::Any,
+		collections::HashMap,
+		sync::{Arc, RwLock},
+		time::{Duration, Instant},
+	},
+	tracing::{debug, trace},
+};
+
+/// In-memory TTL cache for responses of hot read-only routes.
+///
+/// Entries are keyed by route + normalized query string (see [`Cache::key`]) and are dropped either
+/// after `ttl` has passed or when [`Cache::invalidate`] is called after a write.
+#[derive(Debug)]
+pub struct Cache {
+	ttl: Duration,
+	entries: RwLock<HashMap<String, Entry>>,
+}
+
+#[derive(Debug)]
+struct Entry {
+	inserted_at: Instant,
+	value: Arc<dyn Any + Send + Sync>,
+}
+
+impl Cache {
+	pub fn new(ttl: Duration) -> Self {
+		Self {
+			ttl,
+			entries: RwLock::new(HashMap::new()),
+		}
+	}
+
+	/// Builds a cache key that does not depend on the order of query parameters.
+	pub fn key(route: &str, query: Option<&str>) -> String {
+		let query = query
+			.unwrap_or_default()
+			.split('&')
+			.filter(|pair| !pair.is_empty())
+			.sorted()
+			.join("&");
+
+		format!("{route}?{query}")
+	}
+
+	pub fn get<T: Clone + Send + Sync + 'static>(&self, key: &str) -> Option<T> {
+		let entries = self
+			.entries
+			.read()
+			.expect("Cache lock should never be poisoned.");
+
+		let entry = entries.get(key)?;
+
+		if entry.inserted_at.elapsed() > self.ttl {
+			trace!("Cache entry for `{key}` expired.");
+			return None;
+		}
+
+		let value = entry.value.downcast_ref::<T>().cloned();
+		trace!("Cache hit for `{key}`.");
+		value
+	}
+
+	pub fn insert<T: Clone + Send + Sync + 'static>(&self, key: String, value: T) {
+		let mut entries = self
+			.entries
+			.write()
+			.expect("Cache lock should never be poisoned.");
+
+		entries.retain(|_, entry| entry.inserted_at.elapsed() <= self.ttl);
+		entries.insert(
+			key,
+			Entry {
+				inserted_at: Instant::now(),
+				value: Arc::new(value),
+			},
+		);
+	}
+
+	/// Drops every entry. Needs to be called whenever data behind a cached route changes.
+	pub fn invalidate(&self) {
+		self.entries
+			.write()
+			.expect("Cache lock should never be poisoned.")
+			.clear();
+
+		debug!("Invalidated response cache.");
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, std::thread};
+
+	#[test]
+	fn key_ignores_query_order() {
+		assert_eq!(
+			Cache::key("/api/maps", Some("name=lionharder&global=true&limit=5")),
+			Cache::key("/api/maps", Some("limit=5&global=true&name=lionharder")),
+		);
+	}
+
+	#[test]
+	fn key_without_query() {
+		assert_eq!(Cache::key("/api/modes", None), "/api/modes?");
+		assert_eq!(Cache::key("/api/modes", Some("")), "/api/modes?");
+		assert_eq!(Cache::key("/api/modes", Some("&&")), "/api/modes?");
+	}
+
+	#[test]
+	fn key_depends_on_route_and_values() {
+		assert_ne!(
+			Cache::key("/api/maps", Some("limit=5")),
+			Cache::key("/api/modes", Some("limit=5"))
+		);
+		assert_ne!(
+			Cache::key("/api/maps", Some("limit=5")),
+			Cache::key("/api/maps", Some("limit=6"))
+		);
+	}
+
+	#[test]
+	fn get_returns_inserted_values() {
+		let cache = Cache::new(Duration::from_secs(60));
+
+		cache.insert(String::from("maps"), vec![1, 2, 3]);
+
+		assert_eq!(cache.get::<Vec<i32>>("maps"), Some(vec![1, 2, 3]));
+		assert_eq!(cache.get::<Vec<i32>>("modes"), None);
+
+		// Asking for the wrong type is a miss, not a panic.
+		assert_eq!(cache.get::<String>("maps"), None);
+	}
+
+	#[test]
+	fn entries_expire_after_ttl() {
+		let cache = Cache::new(Duration::from_millis(250));
+
+		cache.insert(String::from("maps"), 1);
+		assert_eq!(cache.get::<i32>("maps"), Some(1));
+
+		thread::sleep(Duration::from_millis(300));
+
+		assert_eq!(cache.get::<i32>("maps"), None);
+	}
+
+	#[test]
+	fn invalidate_drops_everything() {
+		let cache = Cache::new(Duration::from_secs(60));
+
+		cache.insert(String::from("maps"), 1);
+		cache.insert(String::from("modes"), 2);
+		cache.invalidate();
+
+		assert_eq!(cache.get::<i32>("maps"), None);
+		assert_eq!(cache.get::<i32>("modes"), None);
+	}
+}
@@ -0,0 +1,156 @@
+use {
+	crate::response::is_streamed,
+	axum::{
+		body::{boxed, Empty, Full},
+		http::{header, HeaderMap, HeaderValue, Method, Request, StatusCode},
+		middleware::Next,
+		response::{IntoResponse, Response},
+	},
+	sha2::{Digest, Sha256},
+	sqlx::types::chrono::{DateTime, FixedOffset},
+	tracing::{error, trace},
+};
+
+/// Adds a strong `ETag` to every successful `GET` response and answers with `304 Not Modified`
+/// if the client already has the current version (`If-None-Match` / `If-Modified-Since`).
+pub async fn conditional_get<B>(request: Request<B>, next: Next<B>) -> Response {
+	let is_get = matches!(*request.method(), Method::GET | Method::HEAD);
+	let if_none_match = request
+		.headers()
+		.get(header::IF_NONE_MATCH)
+		.cloned();
+	let if_modified_since = parse_http_date(request.headers(), header::IF_MODIFIED_SINCE);
+
+	let response = next.run(request).await;
+
+	if !is_get || response.status() != StatusCode::OK || is_streamed(&response) {
+		return response;
+	}
+
+	let (mut parts, body) = response.into_parts();
+
+	let bytes = match hyper::body::to_bytes(body).await {
+		Ok(bytes) => bytes,
+		Err(err) => {
+			error!("Failed to buffer response body: {err:?}");
+			return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+		}
+	};
+
+	let etag = format!("\"{:x}\"", Sha256::digest(&bytes));
+
+	parts.headers.insert(
+		header::ETAG,
+		HeaderValue::from_str(&etag).expect("Hex digests are valid header values."),
+	);
+
+	let last_modified = parse_http_date(&parts.headers, header::LAST_MODIFIED);
+
+	if not_modified(if_none_match.as_ref(), if_modified_since, &etag, last_modified) {
+		trace!("Not modified: {etag}");
+		parts.status = StatusCode::NOT_MODIFIED;
+		parts
+			.headers
+			.remove(header::CONTENT_TYPE);
+		parts
+			.headers
+			.remove(header::CONTENT_LENGTH);
+		return Response::from_parts(parts, boxed(Empty::new()));
+	}
+
+	Response::from_parts(parts, boxed(Full::from(bytes)))
+}
+
+/// Whether a client that sent `if_none_match` / `if_modified_since` already has the version
+/// described by `etag` and `last_modified`. `If-None-Match` takes precedence over
+/// `If-Modified-Since` (RFC 7232, section 6), and weak tags match by their opaque part.
+fn not_modified(
+	if_none_match: Option<&HeaderValue>,
+	if_modified_since: Option<DateTime<FixedOffset>>,
+	etag: &str,
+	last_modified: Option<DateTime<FixedOffset>>,
+) -> bool {
+	match if_none_match {
+		Some(if_none_match) => if_none_match
+			.to_str()
+			.map(|tags| {
+				tags.split(',')
+					.map(str::trim)
+					.any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+			})
+			.unwrap_or(false),
+		None => match (if_modified_since, last_modified) {
+			(Some(since), Some(last_modified)) => last_modified <= since,
+			_ => false,
+		},
+	}
+}
+
+fn parse_http_date(headers: &HeaderMap, name: header::HeaderName) -> Option<DateTime<FixedOffset>> {
+	headers
+		.get(name)?
+		.to_str()
+		.ok()
+		.and_then(|date| DateTime::parse_from_rfc2822(date).ok())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const ETAG: &str = "\"abc123\"";
+
+	fn date(date: &str) -> Option<DateTime<FixedOffset>> {
+		Some(DateTime::parse_from_rfc2822(date).unwrap())
+	}
+
+	fn if_none_match(tags: &'static str) -> Option<HeaderValue> {
+		Some(HeaderValue::from_static(tags))
+	}
+
+	#[test]
+	fn matching_etag() {
+		assert!(not_modified(if_none_match("\"abc123\"").as_ref(), None, ETAG, None));
+		assert!(!not_modified(if_none_match("\"def456\"").as_ref(), None, ETAG, None));
+	}
+
+	#[test]
+	fn etag_in_a_list() {
+		let tags = if_none_match("\"def456\", \"abc123\" ,\"ghi789\"");
+
+		assert!(not_modified(tags.as_ref(), None, ETAG, None));
+	}
+
+	#[test]
+	fn wildcard_matches_anything() {
+		assert!(not_modified(if_none_match("*").as_ref(), None, ETAG, None));
+	}
+
+	#[test]
+	fn weak_etag_matches() {
+		assert!(not_modified(if_none_match("W/\"abc123\"").as_ref(), None, ETAG, None));
+	}
+
+	#[test]
+	fn modified_since() {
+		let last_modified = date("Wed, 21 Oct 2015 07:28:00 GMT");
+
+		assert!(not_modified(None, last_modified, ETAG, last_modified));
+		assert!(not_modified(None, date("Thu, 22 Oct 2015 07:28:00 GMT"), ETAG, last_modified));
+		assert!(!not_modified(None, date("Tue, 20 Oct 2015 07:28:00 GMT"), ETAG, last_modified));
+	}
+
+	#[test]
+	fn modified_since_needs_last_modified() {
+		assert!(!not_modified(None, date("Wed, 21 Oct 2015 07:28:00 GMT"), ETAG, None));
+		assert!(!not_modified(None, None, ETAG, None));
+	}
+
+	#[test]
+	fn if_none_match_takes_precedence() {
+		let last_modified = date("Wed, 21 Oct 2015 07:28:00 GMT");
+		let tags = if_none_match("\"def456\"");
+
+		assert!(!not_modified(tags.as_ref(), last_modified, ETAG, last_modified));
+	}
+}
@@ -0,0 +1,642 @@
+use {
+	crate::state::APIState,
+	hmac::{Hmac, Mac},
+	reqwest::{redirect, Url},
+	schnose_api::{
+		error::{Error, Result},
+		models::{RecordEvent, WebhookQuery},
+	},
+	sha2::Sha256,
+	std::{
+		future::Future,
+		net::{IpAddr, SocketAddr},
+		sync::{Arc, Mutex},
+		time::Duration,
+	},
+	tokio::sync::{broadcast::error::RecvError, Semaphore},
+	tracing::{debug, error, warn},
+};
+
+/// Deliveries are given up on after this many failed attempts.
+const MAX_ATTEMPTS: u8 = 5;
+
+/// Delay before the first retry; doubled after every further failure.
+const BASE_BACKOFF: Duration = Duration::from_secs(2);
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How many deliveries (including their retries) may be in flight at once. Once all are taken,
+/// new events wait, and the broadcast channel drops the oldest ones if that takes too long.
+const MAX_CONCURRENT_DELIVERIES: usize = 64;
+
+/// Forwards every [`RecordEvent`] to all webhooks whose filter matches it.
+pub async fn deliver_record_events(state: APIState) {
+	let mut events = state.record_events.subscribe();
+	let permits = Arc::new(Semaphore::new(MAX_CONCURRENT_DELIVERIES));
+
+	loop {
+		let event = match events.recv().await {
+			Ok(event) => event,
+			Err(RecvError::Lagged(skipped)) => {
+				warn!("Webhook delivery lagged behind; skipped {skipped} records.");
+				continue;
+			}
+			Err(RecvError::Closed) => return,
+		};
+
+		let webhooks = match state
+			.webhooks
+			.get(|| load_webhooks(&state))
+			.await
+		{
+			Ok(webhooks) => webhooks,
+			Err(err) => {
+				error!("Failed to fetch webhooks: {err:?}");
+				continue;
+			}
+		};
+
+		for webhook in webhooks.iter() {
+			if !webhook.filter.0.matches(&event) {
+				continue;
+			}
+
+			let permit = Arc::clone(&permits)
+				.acquire_owned()
+				.await
+				.expect("The semaphore is never closed.");
+
+			let delivery = deliver(webhook.clone(), event.clone(), state.clone());
+
+			tokio::spawn(async move {
+				delivery.await;
+				drop(permit);
+			});
+		}
+	}
+}
+
+async fn load_webhooks(state: &APIState) -> Result<Vec<WebhookQuery>> {
+	Ok(sqlx::query_as("SELECT * FROM webhooks")
+		.fetch_all(state.db())
+		.await?)
+}
+
+/// All registered webhooks, loaded lazily and kept until [`Webhooks::invalidate`] is called.
+/// Saves a query for every single record, since webhooks are rarely added or removed.
+#[derive(Debug, Default)]
+pub struct Webhooks {
+	cached: Mutex<CachedWebhooks>,
+}
+
+#[derive(Debug, Default)]
+struct CachedWebhooks {
+	/// Bumped on every invalidation, so a load that was already running cannot store a list
+	/// that is out of date by the time it finishes.
+	generation: u64,
+	webhooks: Option<Arc<Vec<WebhookQuery>>>,
+}
+
+impl Webhooks {
+	/// Returns the cached list, calling `load` first if there is none.
+	pub async fn get<F, Fut>(&self, load: F) -> Result<Arc<Vec<WebhookQuery>>>
+	where
+		F: FnOnce() -> Fut,
+		Fut: Future<Output = Result<Vec<WebhookQuery>>>,
+	{
+		let generation = {
+			let cached = self.lock();
+
+			if let Some(webhooks) = &cached.webhooks {
+				return Ok(Arc::clone(webhooks));
+			}
+
+			cached.generation
+		};
+
+		let webhooks = Arc::new(load().await?);
+		let mut cached = self.lock();
+
+		if cached.generation == generation {
+			cached.webhooks = Some(Arc::clone(&webhooks));
+		}
+
+		Ok(webhooks)
+	}
+
+	/// Needs to be called whenever a webhook is added or removed.
+	pub fn invalidate(&self) {
+		let mut cached = self.lock();
+
+		cached.generation += 1;
+		cached.webhooks = None;
+
+		debug!("Invalidated webhook cache.");
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<'_, CachedWebhooks> {
+		self.cached
+			.lock()
+			.expect("Webhook cache lock should never be poisoned.")
+	}
+}
+
+/// Rejects webhook URLs that are not HTTP(S) or that resolve to an address inside our own
+/// network (loopback, link-local, private ranges, ...).
+pub async fn check_url(url: &str) -> Result<()> {
+	let url = Url::parse(url).map_err(|_| Error::InvalidInput("Invalid webhook URL."))?;
+
+	resolve(&url, is_public)
+		.await
+		.map(|_| ())
+		.map_err(Error::InvalidInput)
+}
+
+/// POSTs `event` to `webhook`, retrying with exponential backoff. Every attempt is logged to
+/// `webhook_deliveries`.
+async fn deliver(webhook: WebhookQuery, event: RecordEvent, state: APIState) {
+	let body = serde_json::to_vec(&event).expect("Records should always serialize.");
+	let delivery = Delivery::new(&webhook.url, &webhook.secret, body);
+
+	let delivered = delivery
+		.send_with_retries(BASE_BACKOFF, is_public, |attempt, status_code, error| {
+			log_delivery(&webhook, &event, attempt, status_code, error, &state)
+		})
+		.await;
+
+	if delivered {
+		debug!("Delivered record #{} to webhook #{}.", event.record.id, webhook.id);
+	} else {
+		warn!("Giving up on delivering record #{} to webhook #{}.", event.record.id, webhook.id);
+	}
+}
+
+/// A signed payload for a single webhook.
+#[derive(Debug)]
+struct Delivery<'a> {
+	url: &'a str,
+	body: Vec<u8>,
+	signature: String,
+}
+
+impl<'a> Delivery<'a> {
+	fn new(url: &'a str, secret: &str, body: Vec<u8>) -> Self {
+		let signature = sign(secret, &body);
+
+		Self { url, body, signature }
+	}
+
+	/// Sends the payload until it is accepted or [`MAX_ATTEMPTS`] is reached, waiting `backoff`
+	/// before the first retry and twice as long before every further one. `on_attempt` is called
+	/// with the outcome of every attempt. Returns whether the payload was accepted.
+	async fn send_with_retries<F, Fut>(
+		&self,
+		mut backoff: Duration,
+		is_allowed: fn(IpAddr) -> bool,
+		mut on_attempt: F,
+	) -> bool
+	where
+		F: FnMut(u8, Option<u16>, Option<String>) -> Fut,
+		Fut: Future<Output = ()>,
+	{
+		for attempt in 1..=MAX_ATTEMPTS {
+			let (status_code, error) = match self.send(is_allowed).await {
+				Ok(status_code) => (Some(status_code), None),
+				Err((status_code, error)) => (status_code, Some(error)),
+			};
+
+			let delivered = error.is_none();
+
+			on_attempt(attempt, status_code, error).await;
+
+			if delivered {
+				return true;
+			}
+
+			if attempt < MAX_ATTEMPTS {
+				tokio::time::sleep(backoff).await;
+				backoff *= 2;
+			}
+		}
+
+		false
+	}
+
+	/// The URL is resolved and checked against `is_allowed` before every attempt, and the
+	/// request is pinned to the checked address so DNS cannot change in between. Redirects are
+	/// not followed.
+	async fn send(
+		&self,
+		is_allowed: fn(IpAddr) -> bool,
+	) -> std::result::Result<u16, (Option<u16>, String)> {
+		let url = Url::parse(self.url).map_err(|err| (None, err.to_string()))?;
+
+		let addr = resolve(&url, is_allowed)
+			.await
+			.map_err(|err| (None, err.to_owned()))?;
+
+		let mut client = reqwest::Client::builder()
+			.timeout(TIMEOUT)
+			.redirect(redirect::Policy::none());
+
+		if let Some(domain) = url.domain() {
+			client = client.resolve(domain, addr);
+		}
+
+		let response = client
+			.build()
+			.map_err(|err| (None, err.to_string()))?
+			.post(url)
+			.header("Content-Type", "application/json")
+			.header("X-Schnose-Event", "record")
+			.header("X-Schnose-Signature", &self.signature)
+			.body(self.body.clone())
+			.send()
+			.await
+			.map_err(|err| (err.status().map(|code| code.as_u16()), err.to_string()))?;
+
+		let status_code = response.status();
+
+		if status_code.is_success() {
+			Ok(status_code.as_u16())
+		} else {
+			Err((Some(status_code.as_u16()), status_code.to_string()))
+		}
+	}
+}
+
+/// Resolves the host of `url` and returns the first address, as long as every address it
+/// resolves to passes `is_allowed`.
+async fn resolve(
+	url: &Url,
+	is_allowed: fn(IpAddr) -> bool,
+) -> std::result::Result<SocketAddr, &'static str> {
+	if !matches!(url.scheme(), "http" | "https") {
+		return Err("Webhook URLs must use HTTP(S).");
+	}
+
+	let port = url
+		.port_or_known_default()
+		.ok_or("Webhook URLs must have a port.")?;
+
+	let addrs = match (url.domain(), url.host_str()) {
+		(Some(domain), _) => tokio::net::lookup_host((domain, port))
+			.await
+			.map_err(|_| "Webhook URL could not be resolved.")?
+			.collect(),
+		(None, Some(ip)) => {
+			let ip = ip
+				.trim_start_matches('[')
+				.trim_end_matches(']')
+				.parse::<IpAddr>()
+				.map_err(|_| "Invalid webhook URL.")?;
+
+			vec![SocketAddr::new(ip, port)]
+		}
+		(None, None) => return Err("Webhook URLs must have a host."),
+	};
+
+	if !addrs
+		.iter()
+		.all(|addr| is_allowed(addr.ip()))
+	{
+		return Err("Webhook URLs must point to a public address.");
+	}
+
+	addrs
+		.into_iter()
+		.next()
+		.ok_or("Webhook URL could not be resolved.")
+}
+
+/// Whether `ip` is reachable over the public internet, i.e. not loopback, link-local, private,
+/// shared (CGNAT), multicast or otherwise reserved.
+fn is_public(ip: IpAddr) -> bool {
+	match ip {
+		IpAddr::V4(ip) => {
+			let [a, b, ..] = ip.octets();
+
+			!(ip.is_unspecified()
+				|| ip.is_loopback()
+				|| ip.is_private()
+				|| ip.is_link_local()
+				|| ip.is_broadcast()
+				|| ip.is_multicast()
+				|| ip.is_documentation()
+				|| a == 0 || (a == 100 && (b & 0xc0) == 64)
+				|| a >= 240)
+		}
+		IpAddr::V6(ip) => {
+			if let Some(ip) = ip.to_ipv4_mapped() {
+				return is_public(ip.into());
+			}
+
+			let [first, ..] = ip.segments();
+
+			!(ip.is_unspecified()
+				|| ip.is_loopback()
+				|| ip.is_multicast()
+				|| (first & 0xfe00) == 0xfc00
+				|| (first & 0xffc0) == 0xfe80)
+		}
+	}
+}
+
+async fn log_delivery(
+	webhook: &WebhookQuery,
+	event: &RecordEvent,
+	attempt: u8,
+	status_code: Option<u16>,
+	error: Option<String>,
+	state: &APIState,
+) {
+	let result = sqlx::query(
+		r#"
+		INSERT INTO webhook_deliveries
+		  (webhook_id, record_id, attempt, status_code, error)
+		VALUES
+		  (?, ?, ?, ?, ?)
+		"#,
+	)
+	.bind(webhook.id)
+	.bind(event.record.id)
+	.bind(attempt)
+	.bind(status_code)
+	.bind(error.map(|error| {
+		error
+			.chars()
+			.take(255)
+			.collect::<String>()
+	}))
+	.execute(state.db())
+	.await;
+
+	if let Err(err) = result {
+		error!("Failed to log webhook delivery: {err:?}");
+	}
+}
+
+/// `sha256=<hex HMAC-SHA256 of the body>`, keyed with the webhook's secret. Receivers should
+/// compute the same and compare it to `X-Schnose-Signature`.
+fn sign(secret: &str, body: &[u8]) -> String {
+	let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+		.expect("HMAC accepts keys of any length.");
+
+	mac.update(body);
+
+	format!("sha256={:x}", mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		axum::{
+			body::Bytes,
+			extract::State,
+			http::{HeaderMap, StatusCode},
+			routing::post,
+			Router, Server,
+		},
+		std::{
+			net::TcpListener,
+			sync::{Arc, Mutex},
+			time::Instant,
+		},
+	};
+
+	/// What the local receiver saw: when each request arrived, its signature and its body.
+	type Received = Arc<Mutex<Vec<(Instant, String, Bytes)>>>;
+
+	/// Accepts webhooks on a random local port. The first `failures` requests are answered with
+	/// `500 Internal Server Error`.
+	fn receiver(failures: usize) -> (String, Received) {
+		let received = Received::default();
+
+		let router = Router::new()
+			.route(
+				"/hook",
+				post(
+					move |State(received): State<Received>, headers: HeaderMap, body: Bytes| async move {
+						let mut received = received.lock().unwrap();
+						let signature = headers["X-Schnose-Signature"]
+							.to_str()
+							.unwrap()
+							.to_owned();
+
+						received.push((Instant::now(), signature, body));
+
+						match received.len() <= failures {
+							true => StatusCode::INTERNAL_SERVER_ERROR,
+							false => StatusCode::OK,
+						}
+					},
+				),
+			)
+			.with_state(Arc::clone(&received));
+
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let addr = listener.local_addr().unwrap();
+
+		tokio::spawn(
+			Server::from_tcp(listener)
+				.unwrap()
+				.serve(router.into_make_service()),
+		);
+
+		(format!("http://{addr}/hook"), received)
+	}
+
+	#[tokio::test]
+	async fn webhooks_are_loaded_once() {
+		let webhooks = Webhooks::default();
+		let loads = Mutex::new(0);
+		let load = || async {
+			*loads.lock().unwrap() += 1;
+			Ok(Vec::new())
+		};
+
+		webhooks.get(load).await.unwrap();
+		webhooks.get(load).await.unwrap();
+		assert_eq!(*loads.lock().unwrap(), 1);
+
+		webhooks.invalidate();
+		webhooks.get(load).await.unwrap();
+		assert_eq!(*loads.lock().unwrap(), 2);
+	}
+
+	#[tokio::test]
+	async fn failed_loads_are_not_cached() {
+		let webhooks = Webhooks::default();
+
+		assert!(webhooks
+			.get(|| async { Err(Error::Database) })
+			.await
+			.is_err());
+
+		assert!(webhooks
+			.get(|| async { Ok(Vec::new()) })
+			.await
+			.is_ok());
+	}
+
+	#[tokio::test]
+	async fn invalidating_during_a_load_discards_it() {
+		let webhooks = Webhooks::default();
+
+		webhooks
+			.get(|| async {
+				webhooks.invalidate();
+				Ok(Vec::new())
+			})
+			.await
+			.unwrap();
+
+		assert!(webhooks.lock().webhooks.is_none());
+	}
+
+	#[test]
+	fn signs_with_hmac_sha256() {
+		assert_eq!(
+			sign("key", b"The quick brown fox jumps over the lazy dog"),
+			"sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+		);
+	}
+
+	#[test]
+	fn rejects_non_public_addresses() {
+		for ip in [
+			"127.0.0.1", "10.0.0.1", "172.16.0.1", "192.168.1.1", "169.254.169.254", "100.64.0.1",
+			"0.0.0.0", "::1", "fe80::1", "fd00::1", "::ffff:127.0.0.1",
+		] {
+			assert!(!is_public(ip.parse().unwrap()), "{ip} should not be public");
+		}
+
+		for ip in [
+			"1.1.1.1", "95.217.0.1", "2606:4700:4700::1111",
+		] {
+			assert!(is_public(ip.parse().unwrap()), "{ip} should be public");
+		}
+	}
+
+	#[tokio::test]
+	async fn check_url_rejects_internal_urls() {
+		for url in [
+			"http://localhost/hook",
+			"http://127.0.0.1:8080/hook",
+			"http://169.254.169.254/latest/meta-data",
+			"http://[::1]/hook",
+			"http://192.168.0.10/hook",
+			"ftp://1.1.1.1/hook",
+			"not a url",
+		] {
+			assert!(check_url(url).await.is_err(), "{url} should be rejected");
+		}
+
+		assert!(check_url("https://1.1.1.1/hook")
+			.await
+			.is_ok());
+	}
+
+	#[tokio::test]
+	async fn refuses_to_send_to_internal_addresses() {
+		let (url, received) = receiver(0);
+		let delivery = Delivery::new(&url, "secret", b"{}".to_vec());
+
+		assert!(delivery.send(is_public).await.is_err());
+		assert!(received.lock().unwrap().is_empty());
+	}
+
+	#[tokio::test]
+	async fn delivers_signed_payloads() {
+		let (url, received) = receiver(0);
+		let body = br#"{"record":{"id":1}}"#.to_vec();
+		let delivery = Delivery::new(&url, "secret", body.clone());
+
+		let mut attempts = Vec::new();
+		let delivered = delivery
+			.send_with_retries(
+				Duration::from_millis(10),
+				|_| true,
+				|attempt, status_code, error| {
+					attempts.push((attempt, status_code, error));
+					async {}
+				},
+			)
+			.await;
+
+		assert!(delivered);
+		assert_eq!(attempts, [(1, Some(200), None)]);
+
+		let received = received.lock().unwrap();
+		let (_, signature, received_body) = &received[0];
+
+		let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+		mac.update(&body);
+		let expected = format!("sha256={:x}", mac.finalize().into_bytes());
+
+		assert_eq!(received.len(), 1);
+		assert_eq!(signature, &expected);
+		assert_eq!(received_body.as_ref(), body.as_slice());
+	}
+
+	#[tokio::test]
+	async fn retries_with_exponential_backoff() {
+		let backoff = Duration::from_millis(50);
+		let (url, received) = receiver(2);
+		let delivery = Delivery::new(&url, "secret", b"{}".to_vec());
+
+		let mut attempts = Vec::new();
+		let delivered = delivery
+			.send_with_retries(
+				backoff,
+				|_| true,
+				|attempt, status_code, _| {
+					attempts.push((attempt, status_code));
+					async {}
+				},
+			)
+			.await;
+
+		assert!(delivered);
+		assert_eq!(
+			attempts,
+			[
+				(1, Some(500)),
+				(2, Some(500)),
+				(3, Some(200))
+			]
+		);
+
+		let received = received.lock().unwrap();
+		let gaps = received
+			.windows(2)
+			.map(|pair| pair[1].0 - pair[0].0)
+			.collect::<Vec<_>>();
+
+		assert!(gaps[0] >= backoff);
+		assert!(gaps[1] >= backoff * 2);
+	}
+
+	#[tokio::test]
+	async fn gives_up_after_max_attempts() {
+		let (url, received) = receiver(usize::MAX);
+		let delivery = Delivery::new(&url, "secret", b"{}".to_vec());
+
+		let mut attempts = 0;
+		let delivered = delivery
+			.send_with_retries(
+				Duration::from_millis(1),
+				|_| true,
+				|_, _, _| {
+					attempts += 1;
+					async {}
+				},
+			)
+			.await;
+
+		assert!(!delivered);
+		assert_eq!(attempts, MAX_ATTEMPTS);
+		assert_eq!(received.lock().unwrap().len(), MAX_ATTEMPTS as usize);
+	}
+}
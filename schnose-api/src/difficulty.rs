@@ -0,0 +1,305 @@
+use {
+	crate::state::APIState,
+	schnose_api::error::Result,
+	sqlx::{FromRow, QueryBuilder},
+	std::{collections::HashMap, time::Duration},
+	tracing::{error, info},
+};
+
+/// Difficulty only shifts as records pile up, so there is no need to recompute it often.
+const RECOMPUTE_INTERVAL: Duration = Duration::from_secs(60 * 60 * 6);
+
+/// Courses completed by fewer players than this are not scored at all. A handful of completions
+/// say little about how hard a course really is.
+pub const MIN_PLAYERS: u32 = 10;
+
+/// How much each signal contributes to [`CourseSignals::score`]. These add up to 1.0.
+const PLAYERS_WEIGHT: f64 = 0.4;
+const SKILL_WEIGHT: f64 = 0.35;
+const PRO_RATIO_WEIGHT: f64 = 0.25;
+
+/// Everything we know about how a course was played in a single mode.
+#[derive(Debug, Clone, FromRow)]
+struct CourseSignals {
+	course_id: u32,
+	mode_id: u8,
+	tier: u8,
+	players: i64,
+	pro_players: i64,
+	player_skill: f64,
+
+	#[sqlx(default)]
+	score: f64,
+	#[sqlx(default)]
+	estimated_tier: u8,
+}
+
+impl CourseSignals {
+	fn pro_ratio(&self) -> f64 {
+		self.pro_players as f64 / self.players as f64
+	}
+}
+
+/// Recomputes `course_difficulty` from the `records` table. Runs for as long as the API does.
+/// Courses below [`MIN_PLAYERS`] are left out, and their previous scores are dropped.
+pub async fn recompute_difficulty(state: APIState) {
+	let mut interval = tokio::time::interval(RECOMPUTE_INTERVAL);
+
+	loop {
+		interval.tick().await;
+
+		match recompute(&state).await {
+			Ok(courses) => info!("Recomputed difficulty of {courses} courses."),
+			Err(err) => error!("Failed to recompute course difficulty: {err:?}"),
+		}
+	}
+}
+
+async fn recompute(state: &APIState) -> Result<usize> {
+	let signals: Vec<CourseSignals> = sqlx::query_as(
+		r#"
+		SELECT
+		  completion.course_id,
+		  completion.mode_id,
+		  course.tier,
+		  COUNT(*) AS players,
+		  CAST(SUM(completion.pro) AS SIGNED) AS pro_players,
+		  CAST(AVG(skill.courses) AS DOUBLE) AS player_skill
+		FROM (
+		  SELECT
+		    course_id,
+		    mode_id,
+		    player_id,
+		    MAX(teleports = 0) AS pro
+		  FROM records
+		  WHERE status = "valid"
+		  GROUP BY course_id, mode_id, player_id
+		) AS completion
+		JOIN (
+		  SELECT
+		    mode_id,
+		    player_id,
+		    COUNT(DISTINCT course_id) AS courses
+		  FROM records
+		  WHERE status = "valid"
+		  GROUP BY mode_id, player_id
+		) AS skill ON skill.mode_id = completion.mode_id AND skill.player_id = completion.player_id
+		JOIN courses AS course ON course.id = completion.course_id
+		GROUP BY completion.course_id, completion.mode_id, course.tier
+		HAVING COUNT(*) >= ?
+		"#,
+	)
+	.bind(MIN_PLAYERS)
+	.fetch_all(state.db())
+	.await?;
+
+	let mut modes = HashMap::<u8, Vec<CourseSignals>>::new();
+
+	for course in signals {
+		modes
+			.entry(course.mode_id)
+			.or_default()
+			.push(course);
+	}
+
+	let courses = modes
+		.into_values()
+		.flat_map(|mut courses| {
+			score(&mut courses);
+			courses
+		})
+		.collect::<Vec<_>>();
+
+	// Replace everything at once, so courses that dropped below `MIN_PLAYERS` don't keep serving
+	// stale scores, and readers never see a half written table.
+	let mut transaction = state.db().begin().await?;
+
+	sqlx::query("DELETE FROM course_difficulty")
+		.execute(&mut transaction)
+		.await?;
+
+	for chunk in courses.chunks(1000) {
+		let mut query = QueryBuilder::new(
+			r#"
+			INSERT INTO course_difficulty
+			  (course_id, mode_id, players, pro_ratio, player_skill, score, estimated_tier)
+			"#,
+		);
+
+		query.push_values(chunk, |mut query, course| {
+			query
+				.push_bind(course.course_id)
+				.push_bind(course.mode_id)
+				.push_bind(course.players)
+				.push_bind(course.pro_ratio())
+				.push_bind(course.player_skill)
+				.push_bind(course.score)
+				.push_bind(course.estimated_tier);
+		});
+
+		query
+			.build()
+			.execute(&mut transaction)
+			.await?;
+	}
+
+	transaction.commit().await?;
+
+	Ok(courses.len())
+}
+
+/// Scores all `courses` of a single mode relative to each other. A course is harder the fewer
+/// players completed it, the more experienced those players are and the fewer of them managed a
+/// Pro run.
+///
+/// The estimated tiers keep the official distribution: if there are 40 tier 1 courses, the 40
+/// lowest scoring courses are estimated as tier 1, and so on.
+fn score(courses: &mut [CourseSignals]) {
+	let players = percentile_ranks(courses, |course| -(course.players as f64));
+	let skill = percentile_ranks(courses, |course| course.player_skill);
+	let pro_ratio = percentile_ranks(courses, |course| -course.pro_ratio());
+
+	for (idx, course) in courses.iter_mut().enumerate() {
+		course.score = players[idx] * PLAYERS_WEIGHT
+			+ skill[idx] * SKILL_WEIGHT
+			+ pro_ratio[idx] * PRO_RATIO_WEIGHT;
+	}
+
+	let mut tiers = courses
+		.iter()
+		.map(|course| course.tier)
+		.collect::<Vec<_>>();
+
+	tiers.sort_unstable();
+	courses.sort_by(|a, b| a.score.total_cmp(&b.score));
+
+	for (course, tier) in courses.iter_mut().zip(tiers) {
+		course.estimated_tier = tier;
+	}
+}
+
+/// Where each course's `key` falls among all `courses`, from 0.0 (lowest) to 1.0 (highest).
+/// Courses with equal keys share the same rank.
+fn percentile_ranks(courses: &[CourseSignals], key: impl Fn(&CourseSignals) -> f64) -> Vec<f64> {
+	let mut keys = courses
+		.iter()
+		.map(&key)
+		.collect::<Vec<_>>();
+
+	keys.sort_by(f64::total_cmp);
+
+	let max_rank = (keys.len().max(2) - 1) as f64;
+
+	courses
+		.iter()
+		.map(|course| {
+			let key = key(course);
+			keys.partition_point(|other| *other < key) as f64 / max_rank
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn course(
+		course_id: u32,
+		tier: u8,
+		players: i64,
+		pro_players: i64,
+		skill: f64,
+	) -> CourseSignals {
+		CourseSignals {
+			course_id,
+			mode_id: 200,
+			tier,
+			players,
+			pro_players,
+			player_skill: skill,
+			score: 0.0,
+			estimated_tier: 0,
+		}
+	}
+
+	#[test]
+	fn percentile_ranks_of_nothing() {
+		assert!(percentile_ranks(&[], |course| course.player_skill).is_empty());
+	}
+
+	#[test]
+	fn percentile_ranks_of_a_single_course() {
+		let ranks = percentile_ranks(&[course(1, 3, 20, 5, 0.5)], |course| course.player_skill);
+
+		assert_eq!(ranks, [0.0]);
+	}
+
+	#[test]
+	fn percentile_ranks_share_ties() {
+		let courses = [
+			course(1, 3, 20, 5, 0.2),
+			course(2, 3, 20, 5, 0.5),
+			course(3, 3, 20, 5, 0.5),
+			course(4, 3, 20, 5, 0.9),
+		];
+
+		let ranks = percentile_ranks(&courses, |course| course.player_skill);
+
+		assert_eq!(ranks, [0.0, 1.0 / 3.0, 1.0 / 3.0, 1.0]);
+	}
+
+	#[test]
+	fn score_of_nothing() {
+		score(&mut []);
+	}
+
+	#[test]
+	fn score_of_a_single_course_keeps_its_tier() {
+		let mut courses = [course(1, 5, 20, 5, 0.5)];
+
+		score(&mut courses);
+
+		assert_eq!(courses[0].score, 0.0);
+		assert_eq!(courses[0].estimated_tier, 5);
+	}
+
+	#[test]
+	fn score_orders_by_difficulty() {
+		// Fewer players, better players and fewer pro runs all mean a harder course.
+		let mut courses = [
+			course(1, 7, 10, 1, 0.9),
+			course(2, 1, 500, 400, 0.1),
+			course(3, 4, 100, 50, 0.5),
+		];
+
+		score(&mut courses);
+
+		let ids = courses
+			.iter()
+			.map(|course| (course.course_id, course.estimated_tier))
+			.collect::<Vec<_>>();
+
+		assert_eq!(ids, [(2, 1), (3, 4), (1, 7)]);
+		assert_eq!(courses[0].score, 0.0);
+		assert_eq!(courses[2].score, 1.0);
+	}
+
+	#[test]
+	fn score_of_identical_courses_is_equal() {
+		let mut courses = [
+			course(1, 2, 20, 5, 0.5),
+			course(2, 6, 20, 5, 0.5),
+		];
+
+		score(&mut courses);
+
+		assert_eq!(courses[0].score, courses[1].score);
+		assert_eq!(
+			courses
+				.iter()
+				.map(|course| course.estimated_tier)
+				.collect::<Vec<_>>(),
+			[2, 6]
+		);
+	}
+}
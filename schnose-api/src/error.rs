@@ -17,6 +17,12 @@ pub enum Error {
 	#[error("No content")]
 	NoContent,
 
+	#[error("Unauthorized")]
+	Unauthorized,
+
+	#[error("{0}")]
+	InvalidInput(&'static str),
+
 	#[error("Found map without courses. Please report this.")]
 	MapWithoutCourses,
 }
@@ -27,6 +33,8 @@ impl IntoResponse for Error {
 			Error::Custom(msg) => (StatusCode::INTERNAL_SERVER_ERROR, Json(msg.to_owned())),
 			err @ Error::Database => (StatusCode::INTERNAL_SERVER_ERROR, Json(err.to_string())),
 			err @ Error::NoContent => (StatusCode::NO_CONTENT, Json(err.to_string())),
+			err @ Error::Unauthorized => (StatusCode::UNAUTHORIZED, Json(err.to_string())),
+			err @ Error::InvalidInput(_) => (StatusCode::BAD_REQUEST, Json(err.to_string())),
 			err @ Error::MapWithoutCourses => {
 				(StatusCode::INTERNAL_SERVER_ERROR, Json(err.to_string()))
 			}
@@ -0,0 +1,266 @@
+use {
+	crate::state::APIState,
+	schnose_api::{
+		error::Result,
+		models::{Record, RecordEvent, RecordQuery},
+	},
+	sqlx::{FromRow, MySql, QueryBuilder},
+	std::{collections::HashMap, time::Duration},
+	tracing::{debug, error, info},
+};
+
+/// How often the database is checked for new records.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Polls the `records` table for rows inserted since the last check and broadcasts them to
+/// everyone subscribed to [`APIState::record_events`]. Runs for as long as the API does.
+pub async fn poll_new_records(state: APIState) {
+	let mut last_id = latest_record_id(&state).await;
+
+	info!("Watching for records newer than #{last_id}.");
+
+	let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+	loop {
+		interval.tick().await;
+
+		match fetch_new_records(&mut last_id, &state).await {
+			Ok(events) => {
+				for event in events {
+					// `Err` just means that nobody is listening right now.
+					let _ = state.record_events.send(event);
+				}
+			}
+			Err(err) => error!("Failed to fetch new records: {err:?}"),
+		}
+	}
+}
+
+async fn latest_record_id(state: &APIState) -> u32 {
+	loop {
+		match sqlx::query_as::<_, (Option<u32>,)>("SELECT MAX(id) FROM records")
+			.fetch_one(state.db())
+			.await
+		{
+			Ok((last_id,)) => return last_id.unwrap_or_default(),
+			Err(err) => {
+				error!("Failed to fetch latest record ID: {err:?}");
+				tokio::time::sleep(POLL_INTERVAL).await;
+			}
+		}
+	}
+}
+
+/// Advances `last_id` past every fetched row, including ones that fail to parse, so a single
+/// broken row cannot stall the feed.
+async fn fetch_new_records(last_id: &mut u32, state: &APIState) -> Result<Vec<RecordEvent>> {
+	let mut query = QueryBuilder::new(RecordQuery::SELECT);
+
+	query
+		.push(" WHERE record.id > ")
+		.push_bind(*last_id)
+		.push(r#" AND record.status = "valid" "#)
+		.push(" ORDER BY record.id ")
+		.push(" LIMIT 500 ");
+
+	let records: Vec<RecordQuery> = query
+		.build_query_as()
+		.fetch_all(state.db())
+		.await?;
+
+	let Some(max_id) = records
+		.iter()
+		.map(|record| record.id)
+		.max()
+	else {
+		return Ok(Vec::new());
+	};
+
+	let records = records
+		.into_iter()
+		.filter_map(|record| {
+			let record_id = record.id;
+			Record::try_from(record)
+				.map_err(|err| error!("Skipping record #{record_id}: {err:?}"))
+				.ok()
+		})
+		.collect::<Vec<_>>();
+
+	let flags = pb_and_wr(&records, state).await?;
+
+	*last_id = max_id;
+
+	let events = records
+		.into_iter()
+		.map(|record| {
+			let (is_pb, is_wr) = flags
+				.get(&record.id)
+				.copied()
+				.unwrap_or_default();
+
+			debug!("New record: {record:?} (PB: {is_pb}, WR: {is_wr})");
+			RecordEvent { record, is_pb, is_wr }
+		})
+		.collect();
+
+	Ok(events)
+}
+
+/// A record is a PB if the player had no valid run on the same course, mode and runtype before it
+/// that is at least as fast. It is a WR if nobody had, and the course is actually global (has a
+/// filter) for that mode. "Before" means an earlier `created_on`, or the same one and a lower
+/// ID, so runs set later (in the same batch or backfilled) never change the verdict.
+///
+/// Computes both flags for the whole batch at once, keyed by record ID.
+async fn pb_and_wr(records: &[Record], state: &APIState) -> Result<HashMap<u32, (bool, bool)>> {
+	if records.is_empty() {
+		return Ok(HashMap::new());
+	}
+
+	let mut query = QueryBuilder::new(
+		r#"
+		SELECT id, time, previous_own, previous_any, has_filter
+		FROM (
+		  SELECT
+		    r.id,
+		    r.time,
+		    MIN(r.time) OVER (
+		      PARTITION BY r.course_id, r.mode_id, r.teleports > 0, r.player_id
+		      ORDER BY r.created_on, r.id
+		      ROWS BETWEEN UNBOUNDED PRECEDING AND 1 PRECEDING
+		    ) AS previous_own,
+		    MIN(r.time) OVER (
+		      PARTITION BY r.course_id, r.mode_id, r.teleports > 0
+		      ORDER BY r.created_on, r.id
+		      ROWS BETWEEN UNBOUNDED PRECEDING AND 1 PRECEDING
+		    ) AS previous_any,
+		    EXISTS (
+		      SELECT 1 FROM filters AS f
+		      WHERE f.course_id = r.course_id AND f.mode_id = r.mode_id
+		    ) AS has_filter
+		  FROM records AS r
+		  JOIN (
+		    SELECT DISTINCT course_id, mode_id, teleports > 0 AS has_teleports
+		    FROM records
+		    WHERE id IN (
+		"#,
+	);
+
+	push_ids(&mut query, records);
+
+	query.push(
+		r#"
+		    )
+		  ) AS batch
+		    ON batch.course_id = r.course_id
+		    AND batch.mode_id = r.mode_id
+		    AND batch.has_teleports = (r.teleports > 0)
+		  WHERE r.status = "valid"
+		) AS ranked
+		WHERE id IN (
+		"#,
+	);
+
+	push_ids(&mut query, records);
+
+	query.push(")");
+
+	let rows: Vec<Ranked> = query
+		.build_query_as()
+		.fetch_all(state.db())
+		.await?;
+
+	Ok(flags(rows))
+}
+
+/// A record together with the best times set before it, see [`pb_and_wr`].
+#[derive(Debug, Clone, Copy, PartialEq, FromRow)]
+struct Ranked {
+	id: u32,
+	time: f64,
+
+	/// The player's fastest earlier run. `None` if this is their first.
+	previous_own: Option<f64>,
+
+	/// The fastest earlier run by anyone. `None` if this is the first.
+	previous_any: Option<f64>,
+
+	has_filter: i64,
+}
+
+fn flags(rows: Vec<Ranked>) -> HashMap<u32, (bool, bool)> {
+	let beats = |time: f64, previous: Option<f64>| previous.is_none_or(|previous| time < previous);
+
+	rows.into_iter()
+		.map(|row| {
+			let is_pb = beats(row.time, row.previous_own);
+			let is_wr = beats(row.time, row.previous_any) && row.has_filter > 0;
+
+			(row.id, (is_pb, is_wr))
+		})
+		.collect()
+}
+
+fn push_ids(query: &mut QueryBuilder<'_, MySql>, records: &[Record]) {
+	let mut separated = query.separated(", ");
+
+	for record in records {
+		separated.push_bind(record.id);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn ranked(id: u32, time: f64, previous_own: Option<f64>, previous_any: Option<f64>) -> Ranked {
+		Ranked {
+			id,
+			time,
+			previous_own,
+			previous_any,
+			has_filter: 1,
+		}
+	}
+
+	#[test]
+	fn two_improving_runs_in_one_batch() {
+		// Both runs arrive in the same poll. The second one beating the first must not take
+		// the PB and WR away from it.
+		let flags = flags(vec![
+			ranked(1, 12.0, Some(15.0), Some(13.0)),
+			ranked(2, 11.0, Some(12.0), Some(12.0)),
+		]);
+
+		assert_eq!(flags[&1], (true, true));
+		assert_eq!(flags[&2], (true, true));
+	}
+
+	#[test]
+	fn first_runs_are_pbs_and_wrs() {
+		let flags = flags(vec![ranked(1, 60.0, None, None)]);
+
+		assert_eq!(flags[&1], (true, true));
+	}
+
+	#[test]
+	fn ties_are_not_improvements() {
+		let flags = flags(vec![
+			ranked(1, 10.0, Some(10.0), Some(9.0)),
+			ranked(2, 9.0, None, Some(9.0)),
+		]);
+
+		assert_eq!(flags[&1], (false, false));
+		assert_eq!(flags[&2], (true, false));
+	}
+
+	#[test]
+	fn courses_without_a_filter_have_no_wrs() {
+		let flags = flags(vec![Ranked {
+			has_filter: 0,
+			..ranked(1, 10.0, None, None)
+		}]);
+
+		assert_eq!(flags[&1], (true, false));
+	}
+}
@@ -0,0 +1,158 @@
+use {
+	crate::response::is_streamed,
+	axum::{
+		body::{boxed, Full},
+		extract::Query,
+		http::{header, Request, StatusCode},
+		middleware::Next,
+		response::{IntoResponse, Response},
+	},
+	schnose_api::serde::deser_opt_list,
+	serde::Deserialize,
+	serde_json::Value,
+	tracing::{error, trace},
+};
+
+/// `?fields=` and `?expand=`, shared by every route that supports them.
+#[derive(Debug, Default, Deserialize)]
+pub struct FieldParams {
+	/// Top-level fields to keep in the response. Everything is kept if this is not given.
+	#[serde(default, deserialize_with = "deser_opt_list")]
+	pub fields: Option<Vec<String>>,
+
+	/// Nested fields that require extra joins. Everything is expanded if this is not given.
+	#[serde(default, deserialize_with = "deser_opt_list")]
+	pub expand: Option<Vec<String>>,
+}
+
+impl FieldParams {
+	/// Whether the nested `field` has to be fetched: it must be expanded and not filtered out by
+	/// `?fields=`.
+	pub fn expands(&self, field: &str) -> bool {
+		let includes = |list: &Option<Vec<String>>| {
+			list.as_ref()
+				.is_none_or(|list| list.iter().any(|item| item == field))
+		};
+
+		includes(&self.expand) && includes(&self.fields)
+	}
+}
+
+/// Strips every top-level field not listed in `?fields=` from successful JSON responses. For
+/// arrays, this applies to each element.
+pub async fn select_fields<B>(request: Request<B>, next: Next<B>) -> Response {
+	let fields = Query::<FieldParams>::try_from_uri(request.uri())
+		.ok()
+		.and_then(|Query(params)| params.fields);
+
+	let response = next.run(request).await;
+
+	let Some(fields) = fields else {
+		return response;
+	};
+
+	if response.status() != StatusCode::OK || is_streamed(&response) {
+		return response;
+	}
+
+	trace!("Selecting fields {fields:?}.");
+
+	let (mut parts, body) = response.into_parts();
+
+	let selected = match hyper::body::to_bytes(body).await {
+		Ok(bytes) => serde_json::from_slice::<Value>(&bytes)
+			.and_then(|mut value| {
+				select(&mut value, &fields);
+				serde_json::to_vec(&value)
+			})
+			.map_err(|err| err.to_string()),
+		Err(err) => Err(err.to_string()),
+	};
+
+	let bytes = match selected {
+		Ok(bytes) => bytes,
+		Err(err) => {
+			error!("Failed to select fields {fields:?}: {err}");
+			return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+		}
+	};
+
+	parts
+		.headers
+		.remove(header::CONTENT_LENGTH);
+
+	Response::from_parts(parts, boxed(Full::from(bytes)))
+}
+
+/// Keeps only the top-level `fields` of `value`, or of each element if it is an array.
+pub fn select(value: &mut Value, fields: &[String]) {
+	match value {
+		Value::Array(values) => {
+			for value in values {
+				select(value, fields);
+			}
+		}
+		Value::Object(object) => object.retain(|key, _| fields.contains(key)),
+		_ => {}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, serde_json::json};
+
+	fn fields(fields: &[&str]) -> Vec<String> {
+		fields
+			.iter()
+			.map(|field| field.to_string())
+			.collect()
+	}
+
+	#[test]
+	fn select_keeps_listed_top_level_fields() {
+		let mut value = json!({ "id": 1, "name": "kz_lionharder", "courses": [{ "id": 1 }] });
+
+		select(&mut value, &fields(&["id", "courses"]));
+
+		assert_eq!(value, json!({ "id": 1, "courses": [{ "id": 1 }] }));
+	}
+
+	#[test]
+	fn select_applies_to_every_array_element() {
+		let mut value = json!([{ "id": 1, "name": "a" }, { "id": 2, "name": "b" }]);
+
+		select(&mut value, &fields(&["name"]));
+
+		assert_eq!(value, json!([{ "name": "a" }, { "name": "b" }]));
+	}
+
+	#[test]
+	fn select_ignores_unknown_fields_and_scalars() {
+		let mut value = json!({ "id": 1 });
+		select(&mut value, &fields(&["does_not_exist"]));
+		assert_eq!(value, json!({}));
+
+		let mut value = json!(69);
+		select(&mut value, &fields(&["id"]));
+		assert_eq!(value, json!(69));
+	}
+
+	#[test]
+	fn expands_respects_fields_and_expand() {
+		let params = FieldParams::default();
+		assert!(params.expands("courses"));
+
+		let params = FieldParams {
+			fields: None,
+			expand: Some(fields(&["mappers"])),
+		};
+		assert!(params.expands("mappers"));
+		assert!(!params.expands("courses"));
+
+		let params = FieldParams {
+			fields: Some(fields(&["id"])),
+			expand: None,
+		};
+		assert!(!params.expands("courses"));
+	}
+}
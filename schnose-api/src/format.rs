@@ -0,0 +1,431 @@
+use {
+	crate::{fields, response::is_streamed, state::APIState},
+	axum::{
+		body::{boxed, Bytes, Full, StreamBody},
+		extract::Query,
+		http::{header, HeaderValue, Request, StatusCode},
+		middleware::Next,
+		response::{IntoResponse, Response},
+	},
+	schnose_api::{
+		error::{yeet, Error, Result},
+		serde::{OutputFormat, OUTPUT_FORMAT},
+	},
+	serde::Serialize,
+	serde_json::Value,
+	sqlx::{mysql::MySqlRow, FromRow, MySql, QueryBuilder},
+	std::{collections::HashMap, io},
+	tokio::sync::mpsc,
+	tokio_stream::{wrappers::ReceiverStream, StreamExt},
+	tracing::{error, trace},
+};
+
+/// How many encoded rows of an NDJSON export may wait for the client before the query is paused.
+const STREAM_BUFFER: usize = 64;
+
+/// Output formats a client can ask for via `?format=` or the `Accept` header. The negotiated
+/// format is available to handlers as an `Extension<Format>`.
+///
+/// Routes that export many rows stream NDJSON themselves, see [`stream_ndjson`]. Everything else
+/// is encoded from the complete JSON body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+	Json,
+	Csv,
+	NdJson,
+	MessagePack,
+}
+
+impl Format {
+	fn from_name(name: &str) -> Option<Self> {
+		match name.to_lowercase().as_str() {
+			"json" => Some(Self::Json),
+			"csv" => Some(Self::Csv),
+			"ndjson" | "jsonl" => Some(Self::NdJson),
+			"msgpack" | "messagepack" => Some(Self::MessagePack),
+			_ => None,
+		}
+	}
+
+	fn from_mime(mime: &str) -> Option<Self> {
+		match mime {
+			"application/json" => Some(Self::Json),
+			"text/csv" => Some(Self::Csv),
+			"application/x-ndjson" | "application/ndjson" => Some(Self::NdJson),
+			"application/msgpack" | "application/x-msgpack" => Some(Self::MessagePack),
+			_ => None,
+		}
+	}
+
+	/// `?format=` wins over `Accept`. Unknown `?format=` values are rejected; `Accept` headers
+	/// we don't understand fall back to JSON.
+	fn from_request<B>(request: &Request<B>) -> Result<Self> {
+		let from_query = request.uri().query().and_then(|query| {
+			query
+				.split('&')
+				.find_map(|pair| pair.strip_prefix("format="))
+		});
+
+		if let Some(name) = from_query {
+			return Self::from_name(name).ok_or(Error::InvalidInput(
+				"`format` must be one of `json`, `csv`, `ndjson` or `msgpack`.",
+			));
+		}
+
+		Ok(request
+			.headers()
+			.get(header::ACCEPT)
+			.and_then(|accept| accept.to_str().ok())
+			.and_then(|accept| {
+				accept
+					.split(',')
+					.filter_map(|mime| mime.split(';').next())
+					.find_map(|mime| Self::from_mime(mime.trim()))
+			})
+			.unwrap_or(Self::Json))
+	}
+
+	fn content_type(&self) -> &'static str {
+		match self {
+			Self::Json => "application/json",
+			Self::Csv => "text/csv; charset=utf-8",
+			Self::NdJson => "application/x-ndjson",
+			Self::MessagePack => "application/msgpack",
+		}
+	}
+
+	fn encode(&self, value: &Value) -> std::result::Result<Vec<u8>, String> {
+		match self {
+			Self::Json => serde_json::to_vec(value).map_err(|err| err.to_string()),
+			Self::Csv => encode_csv(value),
+			Self::NdJson => encode_ndjson(value),
+			Self::MessagePack => rmp_serde::to_vec_named(value).map_err(|err| err.to_string()),
+		}
+	}
+}
+
+/// Re-encodes successful JSON responses into whatever format the client asked for.
+pub async fn negotiate_format<B>(mut request: Request<B>, next: Next<B>) -> Response {
+	let format = match Format::from_request(&request) {
+		Ok(format) => format,
+		Err(err) => return err.into_response(),
+	};
+
+	request.extensions_mut().insert(format);
+
+	let mut response = next.run(request).await;
+
+	response
+		.headers_mut()
+		.insert(header::VARY, HeaderValue::from_static("Accept"));
+
+	if format == Format::Json || response.status() != StatusCode::OK || is_streamed(&response) {
+		return response;
+	}
+
+	trace!("Encoding response as {format:?}.");
+
+	let (mut parts, body) = response.into_parts();
+
+	let encoded = match hyper::body::to_bytes(body).await {
+		Ok(bytes) => serde_json::from_slice::<Value>(&bytes)
+			.map_err(|err| err.to_string())
+			.and_then(|value| format.encode(&value)),
+		Err(err) => Err(err.to_string()),
+	};
+
+	let bytes = match encoded {
+		Ok(bytes) => bytes,
+		Err(err) => {
+			error!("Failed to encode response as {format:?}: {err}");
+			return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+		}
+	};
+
+	parts
+		.headers
+		.insert(header::CONTENT_TYPE, HeaderValue::from_static(format.content_type()));
+	parts
+		.headers
+		.remove(header::CONTENT_LENGTH);
+
+	Response::from_parts(parts, boxed(Full::from(bytes)))
+}
+
+/// Makes `?steam_id_format=` and `?time_format=` available to the serializers while the
+/// request is being handled.
+pub async fn scope_output_format<B>(request: Request<B>, next: Next<B>) -> Response {
+	let Ok(Query(output_format)) = Query::<OutputFormat>::try_from_uri(request.uri()) else {
+		return Error::InvalidInput(
+			"`steam_id_format` must be one of `steam2`, `steam3`, `steam64` or `account_id`, and \
+			 `time_format` one of `rfc3339` or `unix`.",
+		)
+		.into_response();
+	};
+
+	trace!("Using {output_format:?}.");
+
+	OUTPUT_FORMAT
+		.scope(output_format, next.run(request))
+		.await
+}
+
+/// Streams every row of `query` to the client as one NDJSON line, converting it with `convert`
+/// and applying `?fields=` row by row. Only the rows in flight are kept in memory, no matter how
+/// many the query yields. Answers with `204` if there are none.
+pub async fn stream_ndjson<Q, T>(
+	mut query: QueryBuilder<'static, MySql>,
+	convert: fn(Q) -> Result<T>,
+	fields: Option<Vec<String>>,
+	state: APIState,
+) -> Result<Response>
+where
+	Q: for<'r> FromRow<'r, MySqlRow> + Send + Unpin + 'static,
+	T: Serialize + 'static,
+{
+	let (sender, mut receiver) = mpsc::channel(STREAM_BUFFER);
+
+	// Rows are serialized on their own task, outside of the request's scope.
+	let output_format = OutputFormat::current();
+
+	tokio::spawn(OUTPUT_FORMAT.scope(output_format, async move {
+		let mut rows = query
+			.build_query_as::<Q>()
+			.fetch(state.db());
+
+		while let Some(row) = rows.next().await {
+			let line = row
+				.map_err(Error::from)
+				.and_then(convert)
+				.and_then(|row| encode_line(&row, fields.as_deref()));
+
+			let failed = line.is_err();
+
+			// Either the client went away or there is nothing sensible left to send.
+			if sender.send(line).await.is_err() || failed {
+				break;
+			}
+		}
+	}));
+
+	let first = match receiver.recv().await {
+		Some(line) => line?,
+		None => yeet!(Error::NoContent),
+	};
+
+	// Headers are already sent by the time later rows fail, so all we can do is cut the response
+	// short.
+	let rest = ReceiverStream::new(receiver).map(|line| {
+		line.map_err(|err| {
+			error!("Failed to stream NDJSON: {err}");
+			io::Error::other(err.to_string())
+		})
+	});
+
+	let lines = tokio_stream::once(Ok(first)).chain(rest);
+
+	Ok(([(header::CONTENT_TYPE, Format::NdJson.content_type())], StreamBody::new(lines))
+		.into_response())
+}
+
+fn encode_line<T: Serialize>(row: &T, fields: Option<&[String]>) -> Result<Bytes> {
+	let mut value = serde_json::to_value(row).map_err(|_| "Failed to serialize row.")?;
+
+	if let Some(fields) = fields {
+		fields::select(&mut value, fields);
+	}
+
+	let mut line = serde_json::to_vec(&value).map_err(|_| "Failed to serialize row.")?;
+	line.push(b'\n');
+
+	Ok(line.into())
+}
+
+/// One line per element if the body is an array, otherwise a single line.
+fn encode_ndjson(value: &Value) -> std::result::Result<Vec<u8>, String> {
+	let values = match value {
+		Value::Array(values) => values.iter().collect(),
+		value => vec![value],
+	};
+
+	let mut bytes = Vec::new();
+
+	for value in values {
+		serde_json::to_writer(&mut bytes, value).map_err(|err| err.to_string())?;
+		bytes.push(b'\n');
+	}
+
+	Ok(bytes)
+}
+
+/// One row per element if the body is an array. Nested objects are flattened into
+/// `parent.child` columns; nested arrays are kept as JSON strings.
+fn encode_csv(value: &Value) -> std::result::Result<Vec<u8>, String> {
+	let values = match value {
+		Value::Array(values) => values.iter().collect(),
+		value => vec![value],
+	};
+
+	let rows = values
+		.into_iter()
+		.map(|value| {
+			let mut row = Vec::new();
+			flatten("", value, &mut row);
+			row
+		})
+		.collect::<Vec<_>>();
+
+	let mut columns = Vec::<&str>::new();
+	let mut column_indices = HashMap::<&str, usize>::new();
+
+	for (column, _) in rows.iter().flatten() {
+		column_indices
+			.entry(column)
+			.or_insert_with(|| {
+				columns.push(column);
+				columns.len() - 1
+			});
+	}
+
+	let mut writer = csv::Writer::from_writer(Vec::new());
+
+	writer
+		.write_record(&columns)
+		.map_err(|err| err.to_string())?;
+
+	for row in &rows {
+		let mut record = vec![""; columns.len()];
+
+		for (column, value) in row {
+			record[column_indices[column.as_str()]] = value;
+		}
+
+		writer
+			.write_record(record)
+			.map_err(|err| err.to_string())?;
+	}
+
+	writer
+		.into_inner()
+		.map_err(|err| err.to_string())
+}
+
+fn flatten(prefix: &str, value: &Value, row: &mut Vec<(String, String)>) {
+	let column = match prefix {
+		"" => String::from("value"),
+		prefix => prefix.to_owned(),
+	};
+
+	match value {
+		Value::Object(fields) => {
+			for (key, value) in fields {
+				let key = match prefix {
+					"" => key.to_owned(),
+					prefix => format!("{prefix}.{key}"),
+				};
+
+				flatten(&key, value, row);
+			}
+		}
+		Value::Null => row.push((column, String::new())),
+		Value::String(string) => row.push((column, string.to_owned())),
+		value => row.push((column, value.to_string())),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, serde_json::json};
+
+	#[test]
+	fn ndjson_writes_one_line_per_element() {
+		let value = json!([{ "id": 1, "name": "kz_lionharder" }, { "id": 2, "name": null }]);
+		let encoded = String::from_utf8(encode_ndjson(&value).unwrap()).unwrap();
+
+		assert_eq!(encoded, "{\"id\":1,\"name\":\"kz_lionharder\"}\n{\"id\":2,\"name\":null}\n");
+	}
+
+	#[test]
+	fn ndjson_writes_single_objects_as_one_line() {
+		let encoded = String::from_utf8(encode_ndjson(&json!({ "id": 1 })).unwrap()).unwrap();
+
+		assert_eq!(encoded, "{\"id\":1}\n");
+	}
+
+	#[test]
+	fn csv_flattens_nested_objects() {
+		let value = json!([
+			{ "id": 1, "player": { "name": "AlphaKeks", "steam_id": "STEAM_1:1:161178172" } },
+			{ "id": 2, "player": { "name": "charlie", "steam_id": null } },
+		]);
+		let encoded = String::from_utf8(encode_csv(&value).unwrap()).unwrap();
+
+		assert_eq!(
+			encoded,
+			"id,player.name,player.steam_id\n1,AlphaKeks,STEAM_1:1:161178172\n2,charlie,\n"
+		);
+	}
+
+	#[test]
+	fn csv_fills_missing_columns() {
+		let value = json!([{ "id": 1 }, { "id": 2, "tier": 3 }, { "tier": 7, "id": 3 }]);
+		let encoded = String::from_utf8(encode_csv(&value).unwrap()).unwrap();
+
+		assert_eq!(encoded, "id,tier\n1,\n2,3\n3,7\n");
+	}
+
+	#[test]
+	fn csv_keeps_nested_arrays_as_json() {
+		let value = json!({ "id": 1, "courses": [{ "stage": 0 }] });
+		let encoded = String::from_utf8(encode_csv(&value).unwrap()).unwrap();
+
+		assert_eq!(encoded, "courses,id\n\"[{\"\"stage\"\":0}]\",1\n");
+	}
+
+	fn request(uri: &str, accept: Option<&str>) -> Request<()> {
+		let mut request = Request::builder().uri(uri);
+
+		if let Some(accept) = accept {
+			request = request.header(header::ACCEPT, accept);
+		}
+
+		request.body(()).unwrap()
+	}
+
+	#[test]
+	fn format_from_query_wins_over_accept() {
+		let request = request("/api/maps?format=csv", Some("application/x-ndjson"));
+
+		assert_eq!(Format::from_request(&request), Ok(Format::Csv));
+	}
+
+	#[test]
+	fn format_from_accept() {
+		let request = request("/api/maps", Some("text/html, application/x-ndjson;q=0.9"));
+
+		assert_eq!(Format::from_request(&request), Ok(Format::NdJson));
+	}
+
+	#[test]
+	fn format_falls_back_to_json_for_unknown_accept() {
+		let request = request("/api/maps", Some("text/html, */*"));
+
+		assert_eq!(Format::from_request(&request), Ok(Format::Json));
+	}
+
+	#[test]
+	fn format_rejects_unknown_query() {
+		let request = request("/api/maps?limit=1&format=xml", None);
+
+		assert!(matches!(Format::from_request(&request), Err(Error::InvalidInput(_))));
+	}
+
+	#[test]
+	fn streamed_lines_apply_fields() {
+		let row = json!({ "id": 1, "name": "kz_lionharder" });
+		let fields = [String::from("id")];
+
+		assert_eq!(encode_line(&row, Some(&fields)).unwrap(), "{\"id\":1}\n");
+		assert_eq!(encode_line(&row, None).unwrap(), "{\"id\":1,\"name\":\"kz_lionharder\"}\n");
+	}
+}
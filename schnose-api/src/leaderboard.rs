@@ -0,0 +1,312 @@
+use {
+	crate::{lookup, state::APIState},
+	gokz_rs::{MapIdentifier, Mode},
+	schnose_api::{
+		error::Result,
+		models::{LeaderboardEntry, Runtype},
+	},
+	sqlx::{
+		types::chrono::{DateTime, Utc},
+		FromRow, MySql, QueryBuilder,
+	},
+};
+
+/// Everything that identifies a single leaderboard: one course, mode and runtype, optionally
+/// frozen at a point in time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Leaderboard {
+	pub course_id: u32,
+	pub mode: Mode,
+	pub runtype: Runtype,
+
+	/// Only records set on servers running at this tickrate count. `None` allows any tickrate.
+	pub tickrate: Option<u8>,
+
+	/// Only records set at or before this point count.
+	pub as_of: Option<DateTime<Utc>>,
+}
+
+impl Leaderboard {
+	/// Defaults to KZTimer and Pro runs.
+	pub fn new(
+		course_id: u32,
+		mode: Option<Mode>,
+		runtype: Option<Runtype>,
+		as_of: Option<DateTime<Utc>>,
+	) -> Self {
+		Self {
+			course_id,
+			mode: mode.unwrap_or(Mode::KZTimer),
+			runtype: runtype.unwrap_or_default(),
+			tickrate: None,
+			as_of,
+		}
+	}
+
+	/// Restricts the leaderboard to records set at `tickrate`.
+	pub fn tickrate(mut self, tickrate: Option<u8>) -> Self {
+		self.tickrate = tickrate;
+		self
+	}
+
+	/// Like [`Leaderboard::new`], but looks up the course by map and stage. Defaults to the main
+	/// course.
+	pub async fn resolve(
+		map: &MapIdentifier,
+		stage: Option<u8>,
+		mode: Option<Mode>,
+		runtype: Option<Runtype>,
+		as_of: Option<DateTime<Utc>>,
+		state: &APIState,
+	) -> Result<Self> {
+		let course_id = lookup::course_id(map, stage.unwrap_or(0), state).await?;
+
+		Ok(Self::new(course_id, mode, runtype, as_of))
+	}
+
+	/// Pushes the conditions a record in `records AS r` has to meet to count towards this
+	/// leaderboard. Starts with `WHERE`.
+	pub fn push_filter(&self, query: &mut QueryBuilder<'_, MySql>) {
+		query
+			.push(r#" WHERE r.status = "valid" "#)
+			.push(" AND r.course_id = ")
+			.push_bind(self.course_id)
+			.push(" AND r.mode_id = ")
+			.push_bind(self.mode as u8)
+			.push(" AND (r.teleports > 0) = ")
+			.push_bind(self.runtype.has_teleports());
+
+		if let Some(tickrate) = self.tickrate {
+			query
+				.push(" AND r.tickrate = ")
+				.push_bind(tickrate);
+		}
+
+		if let Some(as_of) = self.as_of {
+			query
+				.push(" AND r.created_on <= ")
+				.push_bind(as_of);
+		}
+	}
+
+	/// Pushes a subquery yielding `id`, `player_id`, `time`, `placement`, `position` (like
+	/// `placement`, but unique) and `total` (the number of players on the leaderboard) of every
+	/// player's personal best on this leaderboard.
+	pub fn push_personal_bests(&self, query: &mut QueryBuilder<'_, MySql>) {
+		query.push(
+			r#"
+			SELECT
+			  pb.id,
+			  pb.player_id,
+			  pb.time,
+			  RANK() OVER (ORDER BY pb.time) AS placement,
+			  ROW_NUMBER() OVER (ORDER BY pb.time, pb.id) AS position,
+			  COUNT(*) OVER () AS total
+			FROM (
+			  SELECT
+			    r.id,
+			    r.player_id,
+			    r.time,
+			    ROW_NUMBER() OVER (
+			      PARTITION BY r.player_id
+			      ORDER BY r.time, r.created_on, r.id
+			    ) AS n
+			  FROM records AS r
+			"#,
+		);
+
+		self.push_filter(query);
+
+		query.push(
+			r#"
+			) AS pb
+			WHERE pb.n = 1
+			"#,
+		);
+	}
+
+	/// Pushes a subquery aliased `alias` that keeps the records which were faster than every
+	/// earlier record on the same course, mode and runtype, i.e. the WR at the time they were set.
+	/// `push_filter` pushes the `JOIN`s and `WHERE` clause deciding which `records AS r` are
+	/// considered at all; if it narrows them down to a single player, the result is their PB
+	/// progression instead.
+	///
+	/// Yields every column of `records` plus `previous_best`, the time that was beaten (`NULL` for
+	/// the first record).
+	pub fn push_wr_at_time<'args>(
+		query: &mut QueryBuilder<'args, MySql>,
+		alias: &str,
+		push_filter: impl FnOnce(&mut QueryBuilder<'args, MySql>),
+	) {
+		query.push(
+			r#"
+			(
+			  SELECT history.*
+			  FROM (
+			    SELECT
+			      r.*,
+			      MIN(r.time) OVER (
+			        PARTITION BY r.course_id, r.mode_id, (r.teleports > 0)
+			        ORDER BY r.created_on, r.id
+			        ROWS BETWEEN UNBOUNDED PRECEDING AND 1 PRECEDING
+			      ) AS previous_best
+			    FROM records AS r
+			"#,
+		);
+
+		push_filter(query);
+
+		query.push(format!(
+			r#"
+			  ) AS history
+			  WHERE history.previous_best IS NULL
+			     OR history.time < history.previous_best
+			) AS {alias}
+			"#
+		));
+	}
+
+	/// The personal bests ranked `offset + 1` through `offset + limit`.
+	pub async fn entries(
+		&self,
+		offset: u64,
+		limit: u64,
+		state: &APIState,
+	) -> Result<Vec<LeaderboardEntry>> {
+		let mut query = QueryBuilder::new(
+			"SELECT ranking.id AS record_id, ranking.placement, ranking.total FROM (",
+		);
+
+		self.push_personal_bests(&mut query);
+
+		query
+			.push(") AS ranking ")
+			.push(" ORDER BY ranking.position ")
+			.push(" LIMIT ")
+			.push_bind(limit)
+			.push(" OFFSET ")
+			.push_bind(offset);
+
+		let ranking: Vec<Placement> = query
+			.build_query_as()
+			.fetch_all(state.db())
+			.await?;
+
+		Self::fetch_entries(ranking, state).await
+	}
+
+	/// `player_id`'s personal best together with the `radius` personal bests above and below it.
+	pub async fn around(
+		&self,
+		player_id: u32,
+		radius: u64,
+		state: &APIState,
+	) -> Result<Vec<LeaderboardEntry>> {
+		let mut query = QueryBuilder::new("WITH ranking AS (");
+
+		self.push_personal_bests(&mut query);
+
+		query
+			.push(
+				r#"
+				), player AS (
+				  SELECT ranking.position
+				  FROM ranking
+				  WHERE ranking.player_id =
+				"#,
+			)
+			.push_bind(player_id)
+			.push(
+				r#"
+				)
+				SELECT
+				  ranking.id AS record_id,
+				  ranking.placement,
+				  ranking.total
+				FROM ranking
+				JOIN player
+				WHERE ranking.position BETWEEN CAST(player.position AS SIGNED) -
+				"#,
+			)
+			// Signed, so positions near the top don't underflow.
+			.push_bind(radius as i64)
+			.push(" AND player.position + ")
+			.push_bind(radius)
+			.push(" ORDER BY ranking.position ");
+
+		let ranking: Vec<Placement> = query
+			.build_query_as()
+			.fetch_all(state.db())
+			.await?;
+
+		Self::fetch_entries(ranking, state).await
+	}
+
+	/// Fetches the full records for `ranking`, keeping its order.
+	pub async fn fetch_entries(
+		ranking: Vec<Placement>,
+		state: &APIState,
+	) -> Result<Vec<LeaderboardEntry>> {
+		let ids = ranking
+			.iter()
+			.map(|placement| placement.record_id)
+			.collect::<Vec<_>>();
+
+		let mut records = lookup::records(&ids, state).await?;
+
+		Ok(ranking
+			.into_iter()
+			.filter_map(|placement| {
+				records
+					.remove(&placement.record_id)
+					.map(|record| LeaderboardEntry {
+						rank: placement.placement as u32,
+						percentile: placement.percentile(),
+						record,
+					})
+			})
+			.collect())
+	}
+}
+
+/// Where a single personal best places on a [`Leaderboard`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromRow)]
+pub struct Placement {
+	pub record_id: u32,
+	pub placement: u64,
+	pub total: i64,
+}
+
+impl Placement {
+	/// The percentage of players whose personal best is this fast or slower.
+	pub fn percentile(&self) -> f64 {
+		(self.total as f64 - self.placement as f64 + 1.0) / self.total as f64 * 100.0
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn placement(placement: u64, total: i64) -> Placement {
+		Placement { record_id: 1, placement, total }
+	}
+
+	#[test]
+	fn first_place_is_the_100th_percentile() {
+		assert_eq!(placement(1, 1).percentile(), 100.0);
+		assert_eq!(placement(1, 250).percentile(), 100.0);
+	}
+
+	#[test]
+	fn last_place_only_beats_itself() {
+		assert_eq!(placement(4, 4).percentile(), 25.0);
+		assert_eq!(placement(250, 250).percentile(), 0.4);
+	}
+
+	#[test]
+	fn percentile_in_between() {
+		assert_eq!(placement(2, 4).percentile(), 75.0);
+		assert_eq!(placement(3, 4).percentile(), 50.0);
+	}
+}
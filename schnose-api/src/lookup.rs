@@ -0,0 +1,127 @@
+use {
+	crate::state::APIState,
+	gokz_rs::{MapIdentifier, PlayerIdentifier, ServerIdentifier},
+	schnose_api::{
+		error::{Error, Result},
+		models::{Record, RecordQuery},
+	},
+	schnosedb::models::PlayerRow,
+	sqlx::QueryBuilder,
+	std::collections::HashMap,
+};
+
+/// Finds the player matching `player`. Names match partially, and also match names the player
+/// went by in the past. Current names win over old ones.
+pub async fn player(player: &PlayerIdentifier, state: &APIState) -> Result<PlayerRow> {
+	let query = match player {
+		PlayerIdentifier::SteamID(steam_id) => {
+			sqlx::query_as("SELECT * FROM players WHERE id = ? LIMIT 1").bind(steam_id.as_id32())
+		}
+		PlayerIdentifier::Name(player_name) => {
+			let player_name = format!("%{player_name}%");
+
+			sqlx::query_as(
+				r#"
+				SELECT *
+				FROM players
+				WHERE name LIKE ?
+				   OR id IN (SELECT player_id FROM player_names WHERE name LIKE ?)
+				ORDER BY name LIKE ? DESC
+				LIMIT 1
+				"#,
+			)
+			.bind(player_name.clone())
+			.bind(player_name.clone())
+			.bind(player_name)
+		}
+	};
+
+	query
+		.fetch_optional(state.db())
+		.await?
+		.ok_or(Error::NoContent)
+}
+
+/// Finds the ID of `stage` on `map`.
+pub async fn course_id(map: &MapIdentifier, stage: u8, state: &APIState) -> Result<u32> {
+	let mut query = QueryBuilder::new(
+		r#"
+		SELECT course.id
+		FROM courses AS course
+		JOIN maps AS map ON map.id = course.map_id
+		"#,
+	);
+
+	match map {
+		MapIdentifier::ID(map_id) => {
+			query
+				.push(" WHERE map.id = ")
+				.push_bind(*map_id);
+		}
+		MapIdentifier::Name(map_name) => {
+			query
+				.push(" WHERE map.name LIKE ")
+				.push_bind(format!("%{map_name}%"));
+		}
+	};
+
+	query
+		.push(" AND course.stage = ")
+		.push_bind(stage)
+		.push(" LIMIT 1 ");
+
+	let (course_id,): (u32,) = query
+		.build_query_as()
+		.fetch_optional(state.db())
+		.await?
+		.ok_or(Error::NoContent)?;
+
+	Ok(course_id)
+}
+
+/// Finds the ID of the server matching `server`. Names match partially.
+pub async fn server_id(server: &ServerIdentifier, state: &APIState) -> Result<u16> {
+	let query = match server {
+		ServerIdentifier::ID(server_id) => {
+			sqlx::query_as("SELECT id FROM servers WHERE id = ? LIMIT 1").bind(*server_id)
+		}
+		ServerIdentifier::Name(server_name) => {
+			sqlx::query_as("SELECT id FROM servers WHERE name LIKE ? LIMIT 1")
+				.bind(format!("%{server_name}%"))
+		}
+	};
+
+	let (server_id,): (u16,) = query
+		.fetch_optional(state.db())
+		.await?
+		.ok_or(Error::NoContent)?;
+
+	Ok(server_id)
+}
+
+/// Fetches the records with the given `ids`, keyed by ID. IDs that don't exist are left out.
+pub async fn records(ids: &[u32], state: &APIState) -> Result<HashMap<u32, Record>> {
+	if ids.is_empty() {
+		return Ok(HashMap::new());
+	}
+
+	let mut query = QueryBuilder::new(RecordQuery::SELECT);
+
+	query.push(" WHERE record.id IN (");
+
+	let mut separated = query.separated(", ");
+
+	for id in ids {
+		separated.push_bind(*id);
+	}
+
+	query.push(")");
+
+	query
+		.build_query_as::<RecordQuery>()
+		.fetch_all(state.db())
+		.await?
+		.into_iter()
+		.map(|record| Ok((record.id, Record::try_from(record)?)))
+		.collect()
+}
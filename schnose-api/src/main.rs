@@ -6,8 +6,21 @@
 
 use {shuttle_secrets::SecretStore, state::APIState, state::ShuttleResult};
 
+mod a2s;
+mod auth;
+mod batch;
+mod cache;
+mod conditional;
+mod delivery;
+mod difficulty;
+mod feed;
+mod fields;
+mod format;
+mod leaderboard;
+mod lookup;
 mod response;
 mod routes;
+mod server_status;
 mod state;
 
 #[shuttle_runtime::main]
@@ -16,7 +29,12 @@ async fn schnoseapi(#[shuttle_secrets::Secrets] secrets: SecretStore) -> Shuttle
 		.get("CONNECTION_STRING")
 		.expect("Missing `CONNECTION_STRING` secret.");
 
-	let state = APIState::new(&connection_string).await;
+	// Admin routes are only mounted if this is set.
+	let admin_key = secrets
+		.get("ADMIN_KEY")
+		.filter(|admin_key| !admin_key.is_empty());
+
+	let state = APIState::new(&connection_string, admin_key).await;
 
 	Ok(state)
 }
@@ -0,0 +1,95 @@
+use {
+	super::{Player, Runtype},
+	crate::error::{Error, Result},
+	gokz_rs::{Mode, Tier},
+	serde::{Deserialize, Serialize},
+	sqlx::FromRow,
+};
+
+/// Two players' personal bests in a single mode and runtype, side by side.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerComparison {
+	pub a: Player,
+	pub b: Player,
+	pub mode: Mode,
+	pub runtype: Runtype,
+
+	/// Shared courses where `a` is faster.
+	pub wins: u32,
+	/// Shared courses where `b` is faster.
+	pub losses: u32,
+	pub ties: u32,
+
+	pub shared: Vec<SharedCompletion>,
+	pub only_a: Vec<Completion>,
+	pub only_b: Vec<Completion>,
+}
+
+/// A player's personal best on a course.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Completion {
+	pub map_id: u16,
+	pub map_name: String,
+	pub course_id: u32,
+	pub stage: u8,
+	pub tier: Tier,
+	pub time: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SharedCompletion {
+	pub map_id: u16,
+	pub map_name: String,
+	pub course_id: u32,
+	pub stage: u8,
+	pub tier: Tier,
+	pub a_time: f64,
+	pub b_time: f64,
+
+	/// `a_time - b_time`. Negative if `a` is faster.
+	pub delta: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct CompletionQuery {
+	pub player_id: u32,
+	pub map_id: u16,
+	pub map_name: String,
+	pub course_id: u32,
+	pub stage: u8,
+	pub tier: u8,
+	pub time: f64,
+}
+
+impl TryFrom<CompletionQuery> for Completion {
+	type Error = Error;
+
+	fn try_from(value: CompletionQuery) -> Result<Self> {
+		Ok(Self {
+			map_id: value.map_id,
+			map_name: value.map_name,
+			course_id: value.course_id,
+			stage: value.stage,
+			tier: value
+				.tier
+				.try_into()
+				.map_err(|_| Error::Custom("Found course with invalid tier."))?,
+			time: value.time,
+		})
+	}
+}
+
+impl SharedCompletion {
+	pub fn new(a: Completion, b_time: f64) -> Self {
+		Self {
+			map_id: a.map_id,
+			map_name: a.map_name,
+			course_id: a.course_id,
+			stage: a.stage,
+			tier: a.tier,
+			a_time: a.time,
+			b_time,
+			delta: a.time - b_time,
+		}
+	}
+}
@@ -0,0 +1,61 @@
+use {
+	super::{Record, Runtype},
+	gokz_rs::{MapIdentifier, Mode, PlayerIdentifier},
+	serde::{Deserialize, Serialize},
+};
+
+/// A newly inserted record, as pushed to `/api/records/stream` subscribers and webhooks.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecordEvent {
+	pub record: Record,
+	pub is_pb: bool,
+	pub is_wr: bool,
+}
+
+/// Narrows down which [`RecordEvent`]s a subscriber is interested in.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordFilter {
+	pub map: Option<MapIdentifier>,
+	pub mode: Option<Mode>,
+	pub runtype: Option<Runtype>,
+	pub player: Option<PlayerIdentifier>,
+	pub wr_only: Option<bool>,
+}
+
+impl RecordFilter {
+	pub fn matches(&self, event: &RecordEvent) -> bool {
+		let record = &event.record;
+
+		let map_matches = match &self.map {
+			None => true,
+			Some(MapIdentifier::ID(map_id)) => record.map_id == *map_id,
+			Some(MapIdentifier::Name(map_name)) => record
+				.map_name
+				.contains(&map_name.to_lowercase()),
+		};
+
+		let mode_matches = match self.mode {
+			None => true,
+			Some(mode) => record.mode == mode,
+		};
+
+		let runtype_matches = match self.runtype {
+			None => true,
+			Some(runtype) => record.runtype == runtype,
+		};
+
+		let player_matches = match &self.player {
+			None => true,
+			Some(PlayerIdentifier::SteamID(steam_id)) => record.player.steam_id == *steam_id,
+			Some(PlayerIdentifier::Name(name)) => record
+				.player
+				.name
+				.to_lowercase()
+				.contains(&name.to_lowercase()),
+		};
+
+		let wr_matches = !self.wr_only.unwrap_or(false) || event.is_wr;
+
+		map_matches && mode_matches && runtype_matches && player_matches && wr_matches
+	}
+}
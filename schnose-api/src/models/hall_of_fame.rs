@@ -0,0 +1,38 @@
+use {
+	super::Player,
+	gokz_rs::SteamID,
+	serde::{Deserialize, Serialize},
+	sqlx::FromRow,
+};
+
+/// A player's placement in one of the hall of fame rankings, e.g. by WR count.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HallOfFameEntry {
+	/// Players with the same count share a rank.
+	pub rank: u32,
+	pub player: Player,
+	pub count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct HallOfFameQuery {
+	pub placement: u64,
+	pub player_id: u32,
+	pub player_name: String,
+	pub is_banned: bool,
+	pub count: i64,
+}
+
+impl From<HallOfFameQuery> for HallOfFameEntry {
+	fn from(value: HallOfFameQuery) -> Self {
+		Self {
+			rank: value.placement as u32,
+			player: Player {
+				name: value.player_name,
+				steam_id: SteamID::from_id32(value.player_id),
+				is_banned: value.is_banned,
+			},
+			count: value.count as u32,
+		}
+	}
+}
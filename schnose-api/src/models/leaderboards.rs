@@ -0,0 +1,17 @@
+use {
+	super::Record,
+	serde::{Deserialize, Serialize},
+};
+
+/// A player's personal best on a course, together with its placement among everyone else's.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+	/// Players with the same time share a rank.
+	pub rank: u32,
+
+	/// The percentage of players on the leaderboard that are this fast or slower.
+	pub percentile: f64,
+
+	#[serde(flatten)]
+	pub record: Record,
+}
@@ -1,7 +1,12 @@
 use {
-	crate::error::{Error, Result},
-	gokz_rs::{SteamID, Tier},
-	schnosedb::{deserialize_datetime, serialize_datetime},
+	super::Player,
+	crate::{
+		error::{Error, Result},
+		serde::{ser_datetime, ser_steam_id},
+	},
+	gokz_rs::{Mode, SteamID, Tier},
+	itertools::Itertools,
+	schnosedb::deserialize_datetime,
 	serde::{Deserialize, Serialize},
 	sqlx::{
 		types::{
@@ -12,18 +17,37 @@ use {
 	},
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Course {
 	pub id: u32,
 	pub stage: u8,
 	pub tier: Tier,
+
+	/// How hard the course turned out to be, judging by its records. One entry per mode.
+	pub difficulty: Vec<CourseDifficulty>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CourseQuery {
 	pub id: Option<u32>,
 	pub stage: Option<u8>,
 	pub tier: Option<Tier>,
+
+	#[serde(default)]
+	pub difficulty: Option<Vec<CourseDifficulty>>,
+}
+
+/// Empirical difficulty of a course in a single mode, see `course_difficulty` in `up.sql`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CourseDifficulty {
+	pub mode: Mode,
+
+	/// 0.0 (easiest) to 1.0 (hardest) among all courses of the same mode.
+	pub score: f64,
+
+	/// The tier this course would have if tiers followed `score`, keeping the same number of
+	/// courses per tier.
+	pub estimated_tier: Tier,
 }
 
 impl TryFrom<CourseQuery> for Course {
@@ -31,7 +55,12 @@ impl TryFrom<CourseQuery> for Course {
 
 	fn try_from(value: CourseQuery) -> Result<Self> {
 		match (value.id, value.stage, value.tier) {
-			(Some(id), Some(stage), Some(tier)) => Ok(Self { id, stage, tier }),
+			(Some(id), Some(stage), Some(tier)) => Ok(Self {
+				id,
+				stage,
+				tier,
+				difficulty: value.difficulty.unwrap_or_default(),
+			}),
 			_ => Err(Error::NoContent),
 		}
 	}
@@ -40,6 +69,8 @@ impl TryFrom<CourseQuery> for Course {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Mapper {
 	pub name: String,
+
+	#[serde(serialize_with = "ser_steam_id")]
 	pub steam_id: SteamID,
 }
 
@@ -63,36 +94,181 @@ impl TryFrom<MapperQuery> for Mapper {
 	}
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Map {
 	pub id: u16,
 	pub name: String,
 	pub global: bool,
+
+	/// Size of the `.bsp` in bytes, i.e. how much a player has to download.
 	pub filesize: u32,
-	pub courses: Vec<Course>,
-	pub mappers: Vec<Mapper>,
-	pub approved_by: Option<SteamID>,
 
-	#[serde(serialize_with = "serialize_datetime", deserialize_with = "deserialize_datetime")]
+	/// The map's Steam Workshop item, if it was uploaded there.
+	pub workshop_id: Option<u32>,
+
+	/// Only included if expanded, see `?expand=`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub courses: Option<Vec<Course>>,
+
+	/// Only included if expanded, see `?expand=`.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub mappers: Option<Vec<Mapper>>,
+
+	pub approved_by: Option<Player>,
+
+	#[serde(serialize_with = "ser_datetime", deserialize_with = "deserialize_datetime")]
 	pub created_on: DateTime<Utc>,
 
-	#[serde(serialize_with = "serialize_datetime", deserialize_with = "deserialize_datetime")]
+	#[serde(serialize_with = "ser_datetime", deserialize_with = "deserialize_datetime")]
 	pub updated_on: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
 pub struct MapQuery {
 	pub id: u16,
 	pub name: String,
 	pub global: bool,
 	pub filesize: u32,
-	pub courses: Json<Vec<CourseQuery>>,
-	pub mappers: Json<Vec<MapperQuery>>,
-	pub approved_by: Option<u32>,
+	pub workshop_id: Option<u32>,
+	pub courses: Option<Json<Vec<CourseQuery>>>,
+	pub mappers: Option<Json<Vec<MapperQuery>>>,
+	pub approved_by: u32,
+	pub approver_name: Option<String>,
+	pub approver_is_banned: Option<bool>,
 
-	#[serde(serialize_with = "serialize_datetime", deserialize_with = "deserialize_datetime")]
+	#[serde(serialize_with = "ser_datetime", deserialize_with = "deserialize_datetime")]
 	pub created_on: DateTime<Utc>,
 
-	#[serde(serialize_with = "serialize_datetime", deserialize_with = "deserialize_datetime")]
+	#[serde(serialize_with = "ser_datetime", deserialize_with = "deserialize_datetime")]
 	pub updated_on: DateTime<Utc>,
 }
+
+impl MapQuery {
+	/// Selects everything needed for a [`MapQuery`]. Courses and mappers are only joined in if
+	/// `courses` / `mappers` are set, and are `NULL` otherwise. Callers append their own `WHERE`
+	/// clause, followed by `GROUP BY map.id`, using `map` as the alias for the `maps` table.
+	pub fn select(courses: bool, mappers: bool) -> String {
+		let mut select = String::from(
+			r#"
+			SELECT
+			  map.id,
+			  map.name,
+			  map.global,
+			  map.filesize,
+			  map.workshop_id,
+			  map.approved_by,
+			  approver.name AS approver_name,
+			  approver.is_banned AS approver_is_banned,
+			  map.created_on,
+			  map.updated_on,
+			"#,
+		);
+
+		select.push_str(if courses { Self::COURSES } else { " NULL AS courses, " });
+		select.push_str(if mappers { Self::MAPPERS } else { " NULL AS mappers " });
+
+		select.push_str(
+			r#"
+			FROM maps AS map
+			LEFT JOIN players AS approver ON approver.id = map.approved_by
+			"#,
+		);
+
+		if courses {
+			select.push_str(" LEFT JOIN courses AS course ON course.map_id = map.id ");
+		}
+
+		if mappers {
+			select.push_str(
+				r#"
+				LEFT JOIN mappers AS mapper ON mapper.map_id = map.id
+				LEFT JOIN players AS player ON player.id = mapper.mapper_id
+				"#,
+			);
+		}
+
+		select
+	}
+
+	const COURSES: &str = r#"
+		JSON_ARRAYAGG(
+		  JSON_OBJECT(
+		    "id",    course.id,
+		    "stage", course.stage,
+		    "tier",  course.tier,
+		    "difficulty", (
+		      SELECT JSON_ARRAYAGG(
+		        JSON_OBJECT(
+		          "mode",           difficulty.mode_id,
+		          "score",          difficulty.score,
+		          "estimated_tier", difficulty.estimated_tier
+		        )
+		      )
+		      FROM course_difficulty AS difficulty
+		      WHERE difficulty.course_id = course.id
+		    )
+		  )
+		) AS courses,
+	"#;
+
+	const MAPPERS: &str = r#"
+		JSON_ARRAYAGG(
+		  JSON_OBJECT(
+		    "name",     player.name,
+		    "steam_id", mapper.mapper_id
+		  )
+		) AS mappers
+	"#;
+}
+
+impl From<MapQuery> for Map {
+	fn from(value: MapQuery) -> Self {
+		// TODO: Do this in the database instead of here. It's fine in this case since there aren't
+		// that many maps anyway, but I would still like to do this properly.
+		let courses = value.courses.map(|courses| {
+			courses
+				.0
+				.into_iter()
+				.flat_map(Course::try_from)
+				.sorted_by(|a, b| a.id.cmp(&b.id))
+				.dedup_by(|a, b| a.id == b.id)
+				.collect()
+		});
+
+		// TODO: Do this in the database instead of here. It's fine in this case since there aren't
+		// that many maps anyway, but I would still like to do this properly.
+		let mappers = value.mappers.map(|mappers| {
+			mappers
+				.0
+				.into_iter()
+				.flat_map(Mapper::try_from)
+				.sorted_by(|a, b| a.steam_id.cmp(&b.steam_id))
+				.dedup_by(|a, b| a.steam_id == b.steam_id)
+				.collect()
+		});
+
+		// Approvers we never saw a record from are not in `players`.
+		let approved_by = (value.approved_by != 0).then(|| Player {
+			name: value
+				.approver_name
+				.unwrap_or_else(|| String::from("unknown")),
+			steam_id: SteamID::from_id32(value.approved_by),
+			is_banned: value
+				.approver_is_banned
+				.unwrap_or(false),
+		});
+
+		Self {
+			id: value.id,
+			name: value.name,
+			global: value.global,
+			filesize: value.filesize,
+			workshop_id: value.workshop_id,
+			courses,
+			mappers,
+			approved_by,
+			created_on: value.created_on,
+			updated_on: value.updated_on,
+		}
+	}
+}
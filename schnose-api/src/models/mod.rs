@@ -2,13 +2,45 @@ mod modes;
 pub use modes::Mode;
 
 mod players;
-pub use players::{Player, PlayerQuery};
+pub use players::{Player, PlayerName, PlayerQuery};
 
 mod maps;
-pub use maps::{Course, CourseQuery, Map, MapQuery, Mapper, MapperQuery};
+pub use maps::{Course, CourseDifficulty, CourseQuery, Map, MapQuery, Mapper, MapperQuery};
 
 mod servers;
-pub use servers::{Server, ServerOwner, ServerOwnerQuery, ServerQuery};
+pub use servers::{
+	OnlinePlayer, Server, ServerApproverQuery, ServerOwner, ServerOwnerQuery, ServerQuery,
+	ServerStatus,
+};
 
 mod records;
-pub use records::{Record, RecordQuery};
+pub use records::{PersonalBest, Record, RecordQuery, WorldRecord};
+
+mod events;
+pub use events::{RecordEvent, RecordFilter};
+
+mod webhooks;
+pub use webhooks::{Webhook, WebhookDelivery, WebhookQuery};
+
+mod runtype;
+pub use runtype::{Runtype, RuntypeFilter};
+
+mod leaderboards;
+pub use leaderboards::LeaderboardEntry;
+
+mod stats;
+pub use stats::{
+	Activity, BusiestMap, CourseStats, HistogramBucket, Percentile, ServerStats, TierOutlier,
+	TierOutlierQuery,
+};
+
+mod comparisons;
+pub use comparisons::{Completion, CompletionQuery, PlayerComparison, SharedCompletion};
+
+mod hall_of_fame;
+pub use hall_of_fame::{HallOfFameEntry, HallOfFameQuery};
+
+mod recommendations;
+pub use recommendations::{
+	Recommendation, RecommendationQuery, Recommendations, TierCompletions, TierCompletionsQuery,
+};
@@ -1,17 +1,25 @@
 use {
 	crate::{
 		error::{yeet, Error, Result},
-		serde::Bool,
+		serde::{ser_datetime, ser_steam_id, Bool},
 	},
 	gokz_rs::SteamID,
-	schnosedb::models::PlayerRow,
+	schnosedb::{
+		deserialize_datetime,
+		models::{PlayerNameRow, PlayerRow},
+	},
 	serde::{Deserialize, Serialize},
-	sqlx::FromRow,
+	sqlx::{
+		types::chrono::{DateTime, Utc},
+		FromRow,
+	},
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Player {
 	pub name: String,
+
+	#[serde(serialize_with = "ser_steam_id")]
 	pub steam_id: SteamID,
 	pub is_banned: bool,
 }
@@ -38,3 +46,25 @@ impl TryFrom<PlayerRow> for Player {
 		})
 	}
 }
+
+/// A name a player has been seen with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerName {
+	pub name: String,
+
+	#[serde(serialize_with = "ser_datetime", deserialize_with = "deserialize_datetime")]
+	pub first_seen: DateTime<Utc>,
+
+	#[serde(serialize_with = "ser_datetime", deserialize_with = "deserialize_datetime")]
+	pub last_seen: DateTime<Utc>,
+}
+
+impl From<PlayerNameRow> for PlayerName {
+	fn from(value: PlayerNameRow) -> Self {
+		Self {
+			name: value.name,
+			first_seen: value.first_seen,
+			last_seen: value.last_seen,
+		}
+	}
+}
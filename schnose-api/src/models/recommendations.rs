@@ -0,0 +1,90 @@
+use {
+	super::Player,
+	crate::error::{Error, Result},
+	gokz_rs::{Mode, Tier},
+	serde::{Deserialize, Serialize},
+	sqlx::FromRow,
+};
+
+/// Courses a player has not finished yet, picked to match what they are comfortable with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Recommendations {
+	pub player: Player,
+	pub mode: Mode,
+
+	/// The highest tier the player has completed a fair share of.
+	pub comfort_tier: Tier,
+
+	pub completions: Vec<TierCompletions>,
+	pub courses: Vec<Recommendation>,
+}
+
+/// How many of the global courses of a tier a player has completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TierCompletions {
+	pub tier: Tier,
+	pub completed: u32,
+	pub total: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromRow)]
+pub struct TierCompletionsQuery {
+	pub tier: u8,
+	pub completed: i64,
+	pub total: i64,
+}
+
+impl TryFrom<TierCompletionsQuery> for TierCompletions {
+	type Error = Error;
+
+	fn try_from(value: TierCompletionsQuery) -> Result<Self> {
+		Ok(Self {
+			tier: value
+				.tier
+				.try_into()
+				.map_err(|_| Error::Custom("Found course with invalid tier."))?,
+			completed: value.completed as u32,
+			total: value.total as u32,
+		})
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Recommendation {
+	pub map_id: u16,
+	pub map_name: String,
+	pub course_id: u32,
+	pub stage: u8,
+	pub tier: Tier,
+
+	/// How many of the players with the most similar completions finished this course.
+	pub similar_players: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct RecommendationQuery {
+	pub map_id: u16,
+	pub map_name: String,
+	pub course_id: u32,
+	pub stage: u8,
+	pub tier: u8,
+	pub similar_players: i64,
+}
+
+impl TryFrom<RecommendationQuery> for Recommendation {
+	type Error = Error;
+
+	fn try_from(value: RecommendationQuery) -> Result<Self> {
+		Ok(Self {
+			map_id: value.map_id,
+			map_name: value.map_name,
+			course_id: value.course_id,
+			stage: value.stage,
+			tier: value
+				.tier
+				.try_into()
+				.map_err(|_| Error::Custom("Found course with invalid tier."))?,
+			similar_players: value.similar_players as u32,
+		})
+	}
+}
@@ -1,8 +1,11 @@
 use {
-	super::{Course, CourseQuery, Player, PlayerQuery, Server, ServerQuery},
-	crate::error::{Error, Result},
+	super::{Course, CourseQuery, Player, PlayerQuery, Runtype, Server, ServerQuery},
+	crate::{
+		error::{Error, Result},
+		serde::{ser_datetime, ser_opt_steam_id},
+	},
 	gokz_rs::{Mode, SteamID},
-	schnosedb::{deserialize_datetime, serialize_datetime},
+	schnosedb::{deserialize_datetime, models::RecordStatus},
 	serde::{Deserialize, Serialize},
 	sqlx::{
 		types::{
@@ -24,9 +27,17 @@ pub struct Record {
 	pub server: Server,
 	pub time: f64,
 	pub teleports: u16,
+	pub runtype: Runtype,
+	pub tickrate: Option<u8>,
 
-	#[serde(serialize_with = "serialize_datetime", deserialize_with = "deserialize_datetime")]
+	#[serde(serialize_with = "ser_datetime", deserialize_with = "deserialize_datetime")]
 	pub created_on: DateTime<Utc>,
+
+	pub status: RecordStatus,
+	pub status_reason: Option<String>,
+
+	#[serde(serialize_with = "ser_opt_steam_id")]
+	pub moderated_by: Option<SteamID>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, FromRow)]
@@ -40,9 +51,77 @@ pub struct RecordQuery {
 	pub server: Json<ServerQuery>,
 	pub time: f64,
 	pub teleports: u16,
+	pub tickrate: Option<u8>,
 
-	#[serde(serialize_with = "serialize_datetime", deserialize_with = "deserialize_datetime")]
+	#[serde(serialize_with = "ser_datetime", deserialize_with = "deserialize_datetime")]
 	pub created_on: DateTime<Utc>,
+
+	pub status: RecordStatus,
+	pub status_reason: Option<String>,
+	pub moderated_by: Option<u32>,
+}
+
+impl RecordQuery {
+	/// Selects everything needed for a [`RecordQuery`]. Callers append their own `WHERE`, `ORDER BY`
+	/// and `LIMIT` clauses, using `record` as the alias for the `records` table.
+	pub const SELECT: &str = r#"
+		SELECT
+		  record.id,
+		  map.id AS map_id,
+		  map.name AS map_name,
+		  JSON_OBJECT(
+		    "id",    _course.id,
+		    "stage", _course.stage,
+		    "tier",  _course.tier,
+		    "difficulty", (
+		      SELECT JSON_ARRAYAGG(
+		        JSON_OBJECT(
+		          "mode",           difficulty.mode_id,
+		          "score",          difficulty.score,
+		          "estimated_tier", difficulty.estimated_tier
+		        )
+		      )
+		      FROM course_difficulty AS difficulty
+		      WHERE difficulty.course_id = _course.id
+		    )
+		  ) AS course,
+		  record.mode_id,
+		  JSON_OBJECT(
+		    "id",        player.id,
+		    "name",      player.name,
+		    "is_banned", player.is_banned
+		  ) AS player,
+		  JSON_OBJECT(
+		    "id", server.id,
+		    "name", server.name,
+		    "ip", server.ip,
+		    "port", server.port,
+		    "tickrate", server.tickrate,
+		    "owned_by", JSON_OBJECT(
+		      "name", server_owner.name,
+		      "steam_id", server_owner.id
+		    ),
+		    "approved_by", JSON_OBJECT(
+		      "steam_id", server.approved_by,
+		      "name", server_approver.name,
+		      "is_banned", server_approver.is_banned
+		    )
+		  ) AS server,
+		  record.time,
+		  record.teleports,
+		  record.tickrate,
+		  record.created_on,
+		  record.status,
+		  record.status_reason,
+		  record.moderated_by
+		FROM records AS record
+		JOIN courses AS _course ON _course.id = record.course_id
+		JOIN maps AS map ON map.id = _course.map_id
+		JOIN players AS player ON player.id = record.player_id
+		JOIN servers AS server ON server.id = record.server_id
+		JOIN players AS server_owner ON server_owner.id = server.owned_by
+		LEFT JOIN players AS server_approver ON server_approver.id = server.approved_by
+	"#;
 }
 
 impl TryFrom<RecordQuery> for Record {
@@ -71,7 +150,45 @@ impl TryFrom<RecordQuery> for Record {
 			server: value.server.0.into(),
 			time: value.time,
 			teleports: value.teleports,
+			runtype: Runtype::from_teleports(value.teleports),
+			tickrate: value.tickrate,
 			created_on: value.created_on,
+			status: value.status,
+			status_reason: value.status_reason,
+			moderated_by: value
+				.moderated_by
+				.and_then(|id| (id != 0).then_some(SteamID::from_id32(id))),
 		})
 	}
 }
+
+/// A record that was the world record at the time it was set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldRecord {
+	#[serde(flatten)]
+	pub record: Record,
+
+	/// How much faster this run was than the previous WR. `None` for the very first one.
+	pub improvement: Option<f64>,
+
+	/// The ID of the record that beat this one, if any.
+	pub beaten_by: Option<u32>,
+
+	/// How long this record was the WR in seconds. Counts up to now (or `as_of`) if it still
+	/// is.
+	pub stood_for: i64,
+}
+
+/// A record that improved a player's personal best on a course.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersonalBest {
+	#[serde(flatten)]
+	pub record: Record,
+
+	/// How much faster this run was than the player's previous best. `None` for their first
+	/// completion.
+	pub improvement: Option<f64>,
+
+	/// The player's rank on the leaderboard right after setting this record.
+	pub rank: u32,
+}
@@ -0,0 +1,47 @@
+use {
+	serde::{Deserialize, Serialize},
+	sqlx::{MySql, QueryBuilder},
+};
+
+/// Whether a run used teleports (`tp`) or not (`pro`). Both are ranked separately.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Runtype {
+	TP,
+	#[default]
+	Pro,
+}
+
+impl Runtype {
+	/// Whether records of this runtype have `teleports > 0`.
+	pub const fn has_teleports(self) -> bool {
+		matches!(self, Self::TP)
+	}
+
+	/// The runtype of a record with `teleports` teleports.
+	pub const fn from_teleports(teleports: u16) -> Self {
+		match teleports {
+			0 => Self::Pro,
+			1.. => Self::TP,
+		}
+	}
+}
+
+/// `?runtype=`, shared by every record, leaderboard and stats route. Routes embed it in their
+/// `Params` with `#[serde(flatten)]`. Flattened fields only ever see query values as strings, so
+/// numbers must not be added here.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuntypeFilter {
+	pub runtype: Option<Runtype>,
+}
+
+impl RuntypeFilter {
+	/// Pushes ` AND (<alias>.teleports > 0) = ?` if a runtype was requested.
+	pub fn push(&self, query: &mut QueryBuilder<'_, MySql>, alias: &str) {
+		if let Some(runtype) = self.runtype {
+			query
+				.push(format!(" AND ({alias}.teleports > 0) = "))
+				.push_bind(runtype.has_teleports());
+		}
+	}
+}
@@ -1,8 +1,19 @@
 use {
-	super::{Mapper, MapperQuery},
+	super::{Mapper, MapperQuery, Player},
+	crate::{
+		error::{Error, Result},
+		serde::{ser_datetime, Bool},
+	},
 	gokz_rs::SteamID,
+	schnosedb::deserialize_datetime,
 	serde::{Deserialize, Serialize},
-	sqlx::{types::Json, FromRow},
+	sqlx::{
+		types::{
+			chrono::{DateTime, Utc},
+			Json,
+		},
+		FromRow,
+	},
 };
 
 pub type ServerOwner = Mapper;
@@ -12,16 +23,56 @@ pub type ServerOwnerQuery = MapperQuery;
 pub struct Server {
 	pub id: u16,
 	pub name: String,
+	pub ip: Option<String>,
+	pub port: Option<u16>,
+	pub tickrate: Option<u8>,
+
+	/// `None` if the owner is unknown or not expanded, see `?expand=`.
 	pub owned_by: Option<ServerOwner>,
-	pub approved_by: Option<SteamID>,
+
+	/// `None` if the server was never approved by anyone.
+	pub approved_by: Option<Player>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, FromRow)]
 pub struct ServerQuery {
 	pub id: u16,
 	pub name: String,
+	pub ip: Option<String>,
+	pub port: Option<u16>,
+	pub tickrate: Option<u8>,
 	pub owned_by: Json<Option<ServerOwnerQuery>>,
-	pub approved_by: Option<u32>,
+	pub approved_by: Json<Option<ServerApproverQuery>>,
+}
+
+/// `name` and `is_banned` are `NULL` for approvers we never saw a record from, since they are not
+/// in `players`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ServerApproverQuery {
+	pub steam_id: u32,
+	pub name: Option<String>,
+	pub is_banned: Option<Bool>,
+}
+
+impl TryFrom<ServerApproverQuery> for Player {
+	type Error = Error;
+
+	fn try_from(value: ServerApproverQuery) -> Result<Self> {
+		if value.steam_id == 0 {
+			return Err(Error::NoContent);
+		}
+
+		Ok(Self {
+			name: value
+				.name
+				.unwrap_or_else(|| String::from("unknown")),
+			steam_id: SteamID::from_id32(value.steam_id),
+			is_banned: value
+				.is_banned
+				.and_then(|is_banned| is_banned.try_into().ok())
+				.unwrap_or(false),
+		})
+	}
 }
 
 impl From<ServerQuery> for Server {
@@ -29,13 +80,41 @@ impl From<ServerQuery> for Server {
 		Self {
 			id: value.id,
 			name: value.name,
+			ip: value.ip,
+			port: value.port,
+			tickrate: value.tickrate,
 			owned_by: value
 				.owned_by
 				.0
 				.and_then(|owner| ServerOwner::try_from(owner).ok()),
 			approved_by: value
 				.approved_by
-				.and_then(|id| (id != 0).then_some(SteamID::from_id32(id))),
+				.0
+				.and_then(|approver| Player::try_from(approver).ok()),
 		}
 	}
 }
+
+/// What a server reported the last time it was queried.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerStatus {
+	pub server_id: u16,
+	pub name: String,
+	pub map: String,
+	pub player_count: u8,
+	pub max_players: u8,
+	pub bots: u8,
+	pub players: Vec<OnlinePlayer>,
+
+	#[serde(serialize_with = "ser_datetime", deserialize_with = "deserialize_datetime")]
+	pub updated_on: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OnlinePlayer {
+	pub name: String,
+	pub score: i32,
+
+	/// Seconds since the player connected.
+	pub duration: f32,
+}
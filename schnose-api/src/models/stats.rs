@@ -0,0 +1,223 @@
+use {
+	super::Runtype,
+	crate::error::{Error, Result},
+	gokz_rs::{Mode, Tier},
+	serde::{Deserialize, Serialize},
+	sqlx::FromRow,
+};
+
+/// How the times on a single course are distributed. Everything except `completions` is based on
+/// each player's personal best.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CourseStats {
+	pub course_id: u32,
+	pub mode: Mode,
+	pub runtype: Runtype,
+	pub completions: u64,
+	pub players: u64,
+	pub fastest: f64,
+	pub slowest: f64,
+	pub mean: f64,
+	pub median: f64,
+	pub percentiles: Vec<Percentile>,
+	pub histogram: Vec<HistogramBucket>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Percentile {
+	pub percentile: u8,
+	pub time: f64,
+}
+
+/// Personal bests with `from <= time < to`. The last bucket also includes `to`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HistogramBucket {
+	pub from: f64,
+	pub to: f64,
+	pub count: u64,
+}
+
+impl CourseStats {
+	/// Linearly interpolated percentile of `times`, which have to be sorted.
+	pub fn percentile(times: &[f64], percentile: f64) -> f64 {
+		let idx = (times.len() - 1) as f64 * percentile / 100.0;
+		let (lower, upper) = (idx.floor() as usize, idx.ceil() as usize);
+
+		times[lower] + (times[upper] - times[lower]) * (idx - lower as f64)
+	}
+
+	/// Splits the range between the fastest and slowest of `times` into `buckets` equally sized
+	/// buckets. `times` have to be sorted.
+	pub fn histogram(times: &[f64], buckets: usize) -> Vec<HistogramBucket> {
+		let (fastest, slowest) = (times[0], times[times.len() - 1]);
+		let width = (slowest - fastest) / buckets as f64;
+		let mut histogram = (0..buckets)
+			.map(|idx| HistogramBucket {
+				from: fastest + width * idx as f64,
+				to: fastest + width * (idx + 1) as f64,
+				count: 0,
+			})
+			.collect::<Vec<_>>();
+
+		for time in times {
+			let idx = match width {
+				width if width > 0.0 => ((time - fastest) / width) as usize,
+				_ => 0,
+			};
+
+			histogram[idx.min(buckets - 1)].count += 1;
+		}
+
+		histogram
+	}
+}
+
+/// A course whose [`estimated_tier`](super::CourseDifficulty::estimated_tier) differs from its
+/// official tier.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TierOutlier {
+	pub course_id: u32,
+	pub map_id: u16,
+	pub map_name: String,
+	pub stage: u8,
+	pub mode: Mode,
+	pub tier: Tier,
+	pub estimated_tier: Tier,
+
+	/// `estimated_tier - tier`. Positive if the course is harder than its tier suggests.
+	pub difference: i8,
+
+	pub score: f64,
+	pub players: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct TierOutlierQuery {
+	pub course_id: u32,
+	pub map_id: u16,
+	pub map_name: String,
+	pub stage: u8,
+	pub mode_id: u8,
+	pub tier: u8,
+	pub estimated_tier: u8,
+	pub score: f64,
+	pub players: u32,
+}
+
+impl TryFrom<TierOutlierQuery> for TierOutlier {
+	type Error = Error;
+
+	fn try_from(value: TierOutlierQuery) -> Result<Self> {
+		Ok(Self {
+			course_id: value.course_id,
+			map_id: value.map_id,
+			map_name: value.map_name,
+			stage: value.stage,
+			mode: value
+				.mode_id
+				.try_into()
+				.expect("Modes in the database should always have valid IDs."),
+			tier: value
+				.tier
+				.try_into()
+				.map_err(|_| Error::Custom("Found course with invalid tier."))?,
+			estimated_tier: value
+				.estimated_tier
+				.try_into()
+				.map_err(|_| Error::Custom("Found course with invalid tier."))?,
+			difference: value.estimated_tier as i8 - value.tier as i8,
+			score: value.score,
+			players: value.players,
+		})
+	}
+}
+
+/// Activity on a single server, based on the records set there.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerStats {
+	pub server_id: u16,
+	pub records: u64,
+
+	/// Records that were the WR at the time they were set.
+	pub wrs: u64,
+
+	pub players: u64,
+	pub busiest_maps: Vec<BusiestMap>,
+
+	/// One entry per month, oldest first.
+	pub activity: Vec<Activity>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct BusiestMap {
+	pub map_id: u16,
+	pub map_name: String,
+	pub records: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct Activity {
+	/// `YYYY-MM`
+	pub month: String,
+	pub records: i64,
+	pub players: i64,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn counts(histogram: &[HistogramBucket]) -> Vec<u64> {
+		histogram
+			.iter()
+			.map(|bucket| bucket.count)
+			.collect()
+	}
+
+	#[test]
+	fn percentile_interpolates() {
+		let times = [10.0, 20.0, 30.0, 40.0];
+
+		assert_eq!(CourseStats::percentile(&times, 0.0), 10.0);
+		assert_eq!(CourseStats::percentile(&times, 50.0), 25.0);
+		assert_eq!(CourseStats::percentile(&times, 100.0), 40.0);
+	}
+
+	#[test]
+	fn percentile_of_a_single_time() {
+		for percentile in [0.0, 25.0, 50.0, 99.0, 100.0] {
+			assert_eq!(CourseStats::percentile(&[42.0], percentile), 42.0);
+		}
+	}
+
+	#[test]
+	fn histogram_of_a_single_time() {
+		let histogram = CourseStats::histogram(&[42.0], 4);
+
+		assert_eq!(counts(&histogram), [1, 0, 0, 0]);
+		assert!(histogram
+			.iter()
+			.all(|bucket| bucket.from == 42.0 && bucket.to == 42.0));
+	}
+
+	#[test]
+	fn histogram_of_equal_times() {
+		// Every bucket has zero width, so everything ends up in the first one.
+		let histogram = CourseStats::histogram(&[15.0, 15.0, 15.0], 3);
+
+		assert_eq!(counts(&histogram), [3, 0, 0]);
+	}
+
+	#[test]
+	fn histogram_last_bucket_includes_upper_bound() {
+		let histogram = CourseStats::histogram(&[0.0, 4.9, 5.0, 9.9, 10.0], 2);
+
+		assert_eq!(histogram[0].from, 0.0);
+		assert_eq!(histogram[0].to, 5.0);
+		assert_eq!(histogram[1].from, 5.0);
+		assert_eq!(histogram[1].to, 10.0);
+
+		// `5.0` starts the second bucket, `10.0` is the slowest time and still counts.
+		assert_eq!(counts(&histogram), [2, 3]);
+	}
+}
@@ -0,0 +1,58 @@
+use {
+	super::RecordFilter,
+	crate::serde::ser_datetime,
+	schnosedb::deserialize_datetime,
+	serde::{Deserialize, Serialize},
+	sqlx::{
+		types::{
+			chrono::{DateTime, Utc},
+			Json,
+		},
+		FromRow,
+	},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Webhook {
+	pub id: u32,
+	pub url: String,
+	pub filter: RecordFilter,
+
+	#[serde(serialize_with = "ser_datetime", deserialize_with = "deserialize_datetime")]
+	pub created_on: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct WebhookQuery {
+	pub id: u32,
+	pub api_key_id: u32,
+	pub url: String,
+	pub secret: String,
+	pub filter: Json<RecordFilter>,
+	pub created_on: DateTime<Utc>,
+}
+
+impl From<WebhookQuery> for Webhook {
+	fn from(value: WebhookQuery) -> Self {
+		Self {
+			id: value.id,
+			url: value.url,
+			filter: value.filter.0,
+			created_on: value.created_on,
+		}
+	}
+}
+
+/// One attempt at delivering a record to a webhook.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct WebhookDelivery {
+	pub id: u32,
+	pub webhook_id: u32,
+	pub record_id: u32,
+	pub attempt: u8,
+	pub status_code: Option<u16>,
+	pub error: Option<String>,
+
+	#[serde(serialize_with = "ser_datetime", deserialize_with = "deserialize_datetime")]
+	pub created_on: DateTime<Utc>,
+}
@@ -1,22 +1,68 @@
 use {
-	axum::{extract::Json, http::StatusCode, response::IntoResponse},
+	axum::{
+		extract::Json,
+		http::{header, HeaderValue, StatusCode},
+		response::IntoResponse,
+	},
 	schnose_api::error::Error,
-	serde::{Deserialize, Serialize},
+	serde::Serialize,
+	sqlx::types::chrono::{DateTime, Utc},
 };
 
 pub type Response<T> = Result<ResponseBody<T>, Error>;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ResponseBody<T>(pub T);
+#[derive(Debug, Clone)]
+pub struct ResponseBody<T> {
+	pub body: T,
+
+	/// Sent as `Last-Modified` so clients can make conditional requests using `If-Modified-Since`.
+	pub last_modified: Option<DateTime<Utc>>,
+}
+
+impl<T> ResponseBody<T> {
+	pub fn last_modified(mut self, last_modified: Option<DateTime<Utc>>) -> Self {
+		self.last_modified = last_modified;
+		self
+	}
+}
 
 impl<T> From<T> for ResponseBody<T> {
-	fn from(json: T) -> Self {
-		Self(json)
+	fn from(body: T) -> Self {
+		Self { body, last_modified: None }
 	}
 }
 
 impl<T: Serialize> IntoResponse for ResponseBody<T> {
 	fn into_response(self) -> axum::response::Response {
-		(StatusCode::OK, Json(self.0)).into_response()
+		let mut response = (StatusCode::OK, Json(self.body)).into_response();
+
+		if let Some(last_modified) = self.last_modified {
+			let last_modified = HeaderValue::from_str(&http_date(&last_modified))
+				.expect("Formatted dates are valid header values.");
+
+			response
+				.headers_mut()
+				.insert(header::LAST_MODIFIED, last_modified);
+		}
+
+		response
 	}
 }
+
+/// Formats a timestamp as an HTTP-date (RFC 7231), e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+pub fn http_date(date: &DateTime<Utc>) -> String {
+	date.format("%a, %d %b %Y %H:%M:%S GMT")
+		.to_string()
+}
+
+/// Streaming responses (SSE and NDJSON) must never be buffered by middleware.
+pub fn is_streamed(response: &axum::response::Response) -> bool {
+	response
+		.headers()
+		.get(header::CONTENT_TYPE)
+		.is_some_and(|content_type| {
+			let content_type = content_type.as_bytes();
+			content_type.starts_with(b"text/event-stream")
+				|| content_type.starts_with(b"application/x-ndjson")
+		})
+}
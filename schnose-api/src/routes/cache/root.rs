@@ -0,0 +1,15 @@
+use {
+	crate::{auth::Admin, state::APIState},
+	axum::{extract::State, http::StatusCode},
+	tracing::trace,
+};
+
+/// Clears the response cache. Ingestion jobs call this after writing to the database.
+#[axum::debug_handler]
+pub async fn delete(_: Admin, State(state): State<APIState>) -> StatusCode {
+	trace!("DELETE /api/cache");
+
+	state.cache.invalidate();
+
+	StatusCode::NO_CONTENT
+}
@@ -0,0 +1,3 @@
+pub mod stats;
+
+pub mod outliers;
@@ -0,0 +1,104 @@
+use {
+	crate::{difficulty, response::Response, state::APIState},
+	axum::extract::{Query, State},
+	gokz_rs::Mode,
+	schnose_api::{
+		error::{yeet, Error},
+		models::{TierOutlier, TierOutlierQuery},
+	},
+	serde::Deserialize,
+	sqlx::QueryBuilder,
+	tracing::{debug, trace},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Params {
+	mode: Option<Mode>,
+	min_difference: Option<u8>,
+	min_players: Option<u32>,
+	limit: Option<u16>,
+}
+
+/// Courses whose empirical difficulty disagrees the most with their official tier.
+#[axum::debug_handler]
+pub async fn get(
+	Query(params): Query<Params>,
+	State(state): State<APIState>,
+) -> Response<Vec<TierOutlier>> {
+	trace!("GET /api/courses/outliers");
+	trace!("{params:?}");
+
+	let mut query = QueryBuilder::new(
+		r#"
+		SELECT
+		  course.id AS course_id,
+		  map.id AS map_id,
+		  map.name AS map_name,
+		  course.stage,
+		  difficulty.mode_id,
+		  course.tier,
+		  difficulty.estimated_tier,
+		  difficulty.score,
+		  difficulty.players
+		FROM course_difficulty AS difficulty
+		JOIN courses AS course ON course.id = difficulty.course_id
+		JOIN maps AS map ON map.id = course.map_id
+		WHERE ABS(CAST(difficulty.estimated_tier AS SIGNED) - CAST(course.tier AS SIGNED)) >=
+		"#,
+	);
+
+	query.push_bind(
+		params
+			.min_difference
+			.unwrap_or(2)
+			.max(1),
+	);
+
+	// Courses below `difficulty::MIN_PLAYERS` are never scored, so only stricter limits matter.
+	query
+		.push(" AND difficulty.players >= ")
+		.push_bind(
+			params
+				.min_players
+				.unwrap_or(difficulty::MIN_PLAYERS),
+		);
+
+	if let Some(mode) = params.mode {
+		query
+			.push(" AND difficulty.mode_id = ")
+			.push_bind(mode as u8);
+	}
+
+	query
+		.push(
+			r#"
+			ORDER BY
+			  ABS(CAST(difficulty.estimated_tier AS SIGNED) - CAST(course.tier AS SIGNED)) DESC,
+			  difficulty.players DESC
+			"#,
+		)
+		.push(" LIMIT ")
+		.push_bind(match params.limit.unwrap_or(100) {
+			0 => 1,
+			limit @ (1..=500) => limit,
+			501.. => 500,
+		});
+
+	let outliers: Vec<TierOutlierQuery> = query
+		.build_query_as()
+		.fetch_all(state.db())
+		.await?;
+
+	if outliers.is_empty() {
+		yeet!(Error::NoContent);
+	}
+
+	debug!("Outliers:\n\t{outliers:?}");
+
+	let outliers = outliers
+		.into_iter()
+		.map(TierOutlier::try_from)
+		.collect::<Result<Vec<_>, _>>()?;
+
+	Ok(outliers.into())
+}
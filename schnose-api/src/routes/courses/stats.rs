@@ -0,0 +1,94 @@
+use {
+	crate::{leaderboard::Leaderboard, response::Response, state::APIState},
+	axum::extract::{Path, Query, State},
+	gokz_rs::Mode,
+	schnose_api::{
+		error::{yeet, Error},
+		models::{CourseStats, Percentile, RuntypeFilter},
+	},
+	serde::Deserialize,
+	sqlx::QueryBuilder,
+	tracing::{debug, trace},
+};
+
+/// The percentiles listed in [`CourseStats::percentiles`].
+const PERCENTILES: [u8; 6] = [10, 25, 50, 75, 90, 99];
+
+#[derive(Debug, Deserialize)]
+pub struct Params {
+	mode: Option<Mode>,
+	#[serde(flatten)]
+	filter: RuntypeFilter,
+	tickrate: Option<u8>,
+	buckets: Option<u8>,
+}
+
+#[axum::debug_handler]
+pub async fn get(
+	Path(course_id): Path<u32>,
+	Query(params): Query<Params>,
+	State(state): State<APIState>,
+) -> Response<CourseStats> {
+	trace!("GET /api/courses/{course_id}/stats");
+	trace!("{params:?}");
+
+	let leaderboard = Leaderboard::new(course_id, params.mode, params.filter.runtype, None)
+		.tickrate(params.tickrate);
+
+	let mut query = QueryBuilder::new("SELECT COUNT(*) FROM records AS r");
+
+	leaderboard.push_filter(&mut query);
+
+	let (completions,): (i64,) = query
+		.build_query_as()
+		.fetch_one(state.db())
+		.await?;
+
+	let mut query = QueryBuilder::new("SELECT pbs.time FROM (");
+
+	leaderboard.push_personal_bests(&mut query);
+
+	query.push(") AS pbs ORDER BY pbs.time");
+
+	let times = query
+		.build_query_as::<(f64,)>()
+		.fetch_all(state.db())
+		.await?
+		.into_iter()
+		.map(|(time,)| time)
+		.collect::<Vec<_>>();
+
+	if times.is_empty() {
+		yeet!(Error::NoContent);
+	}
+
+	let buckets = match params.buckets.unwrap_or(20) {
+		0 => 1,
+		buckets @ (1..=100) => buckets,
+		101.. => 100,
+	};
+
+	let stats = CourseStats {
+		course_id,
+		mode: leaderboard.mode,
+		runtype: leaderboard.runtype,
+		completions: completions as u64,
+		players: times.len() as u64,
+		fastest: times[0],
+		slowest: times[times.len() - 1],
+		mean: times.iter().sum::<f64>() / times.len() as f64,
+		median: CourseStats::percentile(&times, 50.0),
+		percentiles: PERCENTILES
+			.into_iter()
+			.map(|percentile| Percentile {
+				percentile,
+				time: CourseStats::percentile(&times, percentile as f64),
+			})
+			.collect(),
+		histogram: CourseStats::histogram(&times, buckets as usize),
+	};
+
+	debug!("Stats:\n\t{stats:?}");
+
+	Ok(stats.into())
+}
@@ -0,0 +1,106 @@
+use {
+	crate::{response::Response, state::APIState},
+	axum::extract::{Query, State},
+	gokz_rs::Mode,
+	schnose_api::{
+		error::{yeet, Error},
+		models::{HallOfFameEntry, HallOfFameQuery, RuntypeFilter},
+		serde::deser_opt_timestamp,
+	},
+	serde::Deserialize,
+	sqlx::{
+		types::chrono::{DateTime, Utc},
+		QueryBuilder,
+	},
+	tracing::{debug, trace},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Params {
+	mode: Option<Mode>,
+	#[serde(flatten)]
+	filter: RuntypeFilter,
+	tier: Option<u8>,
+
+	#[serde(default, deserialize_with = "deser_opt_timestamp")]
+	from: Option<DateTime<Utc>>,
+	#[serde(default, deserialize_with = "deser_opt_timestamp")]
+	to: Option<DateTime<Utc>>,
+
+	limit: Option<u16>,
+}
+
+/// Ranks players by the number of courses they completed. `?runtype=pro` only counts Pro
+/// completions. With `from` and/or `to`, only courses first completed in that window count.
+#[axum::debug_handler]
+pub async fn get(
+	Query(params): Query<Params>,
+	State(state): State<APIState>,
+) -> Response<Vec<HallOfFameEntry>> {
+	trace!("GET /api/hall-of-fame/completions");
+	trace!("{params:?}");
+
+	let mut query = QueryBuilder::new(
+		r#"
+		SELECT
+		  RANK() OVER (ORDER BY COUNT(*) DESC) AS placement,
+		  player.id AS player_id,
+		  player.name AS player_name,
+		  player.is_banned,
+		  COUNT(*) AS count
+		FROM (
+		  SELECT
+		    r.player_id,
+		    r.course_id,
+		    MIN(r.created_on) AS completed_on
+		  FROM records AS r
+		  JOIN filters AS filter ON filter.course_id = r.course_id AND filter.mode_id = r.mode_id
+		  JOIN courses AS course ON course.id = r.course_id
+		  WHERE r.status = "valid"
+		"#,
+	);
+
+	query
+		.push(" AND r.mode_id = ")
+		.push_bind(params.mode.unwrap_or(Mode::KZTimer) as u8);
+
+	super::push_course_filters(&mut query, params.filter, params.tier);
+
+	query.push(
+		r#"
+		  GROUP BY r.player_id, r.course_id
+		) AS completion
+		JOIN players AS player ON player.id = completion.player_id
+		WHERE TRUE
+		"#,
+	);
+
+	super::push_window(&mut query, "completion.completed_on", params.from, params.to);
+
+	query
+		.push(" GROUP BY player.id ")
+		.push(" ORDER BY count DESC, player.id ")
+		.push(" LIMIT ")
+		.push_bind(match params.limit.unwrap_or(100) {
+			0 => 1,
+			limit @ (1..=500) => limit,
+			501.. => 500,
+		});
+
+	let players: Vec<HallOfFameQuery> = query
+		.build_query_as()
+		.fetch_all(state.db())
+		.await?;
+
+	if players.is_empty() {
+		yeet!(Error::NoContent);
+	}
+
+	debug!("Completion leaders:\n\t{players:?}");
+
+	Ok(players
+		.into_iter()
+		.map(HallOfFameEntry::from)
+		.collect::<Vec<_>>()
+		.into())
+}
@@ -0,0 +1,50 @@
+use {
+	schnose_api::models::RuntypeFilter,
+	sqlx::{
+		types::chrono::{DateTime, Utc},
+		MySql, QueryBuilder,
+	},
+};
+
+pub mod wrs;
+
+pub mod completions;
+
+// TODO: There is no points ranking yet. When `/api/hall-of-fame/points` is added, it should also
+// take `?around=<player>&radius=N` like `/api/maps/:ident/leaderboard` does (see
+// `Leaderboard::around`), so mid-ranked players can find themselves without paging.
+
+/// Narrows `records AS r` joined with `courses AS course` down to a runtype and tier.
+fn push_course_filters(
+	query: &mut QueryBuilder<'_, MySql>,
+	filter: RuntypeFilter,
+	tier: Option<u8>,
+) {
+	filter.push(query, "r");
+
+	if let Some(tier) = tier {
+		query
+			.push(" AND course.tier = ")
+			.push_bind(tier);
+	}
+}
+
+/// Only keeps rows where `column` lies between `from` and `to` (both inclusive).
+fn push_window(
+	query: &mut QueryBuilder<'_, MySql>,
+	column: &str,
+	from: Option<DateTime<Utc>>,
+	to: Option<DateTime<Utc>>,
+) {
+	if let Some(from) = from {
+		query
+			.push(format!(" AND {column} >= "))
+			.push_bind(from);
+	}
+
+	if let Some(to) = to {
+		query
+			.push(format!(" AND {column} <= "))
+			.push_bind(to);
+	}
+}
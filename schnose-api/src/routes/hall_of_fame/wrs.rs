@@ -0,0 +1,144 @@
+use {
+	crate::{leaderboard::Leaderboard, response::Response, state::APIState},
+	axum::extract::{Query, State},
+	gokz_rs::Mode,
+	schnose_api::{
+		error::{yeet, Error},
+		models::{HallOfFameEntry, HallOfFameQuery, RuntypeFilter},
+		serde::deser_opt_timestamp,
+	},
+	serde::Deserialize,
+	sqlx::{
+		types::chrono::{DateTime, Utc},
+		MySql, QueryBuilder,
+	},
+	tracing::{debug, trace},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Params {
+	mode: Option<Mode>,
+	#[serde(flatten)]
+	filter: RuntypeFilter,
+	tier: Option<u8>,
+
+	#[serde(default, deserialize_with = "deser_opt_timestamp")]
+	from: Option<DateTime<Utc>>,
+	#[serde(default, deserialize_with = "deser_opt_timestamp")]
+	to: Option<DateTime<Utc>>,
+
+	limit: Option<u16>,
+}
+
+/// Ranks players by the number of WRs they currently hold. With `from` and/or `to`, ranks them by
+/// the number of WRs they set in that window instead, whether they still stand or not. Only
+/// courses with a filter for the mode count.
+#[axum::debug_handler]
+pub async fn get(
+	Query(params): Query<Params>,
+	State(state): State<APIState>,
+) -> Response<Vec<HallOfFameEntry>> {
+	trace!("GET /api/hall-of-fame/wrs");
+	trace!("{params:?}");
+
+	let mut query = QueryBuilder::new(
+		r#"
+		SELECT
+		  RANK() OVER (ORDER BY COUNT(*) DESC) AS placement,
+		  player.id AS player_id,
+		  player.name AS player_name,
+		  player.is_banned,
+		  COUNT(*) AS count
+		FROM
+		"#,
+	);
+
+	match params.from.is_some() || params.to.is_some() {
+		true => Leaderboard::push_wr_at_time(&mut query, "wr", |query| push_filter(query, &params)),
+		false => push_current_wrs(&mut query, |query| push_filter(query, &params)),
+	}
+
+	query.push(
+		r#"
+		JOIN players AS player ON player.id = wr.player_id
+		WHERE TRUE
+		"#,
+	);
+
+	super::push_window(&mut query, "wr.created_on", params.from, params.to);
+
+	query
+		.push(" GROUP BY player.id ")
+		.push(" ORDER BY count DESC, player.id ")
+		.push(" LIMIT ")
+		.push_bind(match params.limit.unwrap_or(100) {
+			0 => 1,
+			limit @ (1..=500) => limit,
+			501.. => 500,
+		});
+
+	let players: Vec<HallOfFameQuery> = query
+		.build_query_as()
+		.fetch_all(state.db())
+		.await?;
+
+	if players.is_empty() {
+		yeet!(Error::NoContent);
+	}
+
+	debug!("WR holders:\n\t{players:?}");
+
+	Ok(players
+		.into_iter()
+		.map(HallOfFameEntry::from)
+		.collect::<Vec<_>>()
+		.into())
+}
+
+/// Only global courses in the requested mode, runtype and tier count.
+fn push_filter(query: &mut QueryBuilder<'_, MySql>, params: &Params) {
+	query
+		.push(
+			r#"
+			JOIN filters AS filter ON filter.course_id = r.course_id AND filter.mode_id = r.mode_id
+			JOIN courses AS course ON course.id = r.course_id
+			WHERE r.status = "valid"
+			"#,
+		)
+		.push(" AND r.mode_id = ")
+		.push_bind(params.mode.unwrap_or(Mode::KZTimer) as u8);
+
+	super::push_course_filters(query, params.filter, params.tier);
+}
+
+/// Like [`Leaderboard::push_wr_at_time`], but only keeps the current WR of every course, mode and
+/// runtype.
+fn push_current_wrs<'args>(
+	query: &mut QueryBuilder<'args, MySql>,
+	push_filter: impl FnOnce(&mut QueryBuilder<'args, MySql>),
+) {
+	query.push(
+		r#"
+		(
+		  SELECT ranked.*
+		  FROM (
+		    SELECT
+		      r.*,
+		      ROW_NUMBER() OVER (
+		        PARTITION BY r.course_id, r.mode_id, (r.teleports > 0)
+		        ORDER BY r.time, r.created_on, r.id
+		      ) AS n
+		    FROM records AS r
+		"#,
+	);
+
+	push_filter(query);
+
+	query.push(
+		r#"
+		  ) AS ranked
+		  WHERE ranked.n = 1
+		) AS wr
+		"#,
+	);
+}
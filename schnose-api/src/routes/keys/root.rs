@@ -0,0 +1,47 @@
+use {
+	crate::{
+		auth::{generate_secret, hash_key, Admin},
+		response::Response,
+		state::APIState,
+	},
+	axum::{extract::State, Json},
+	serde::{Deserialize, Serialize},
+	tracing::{info, trace},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Body {
+	name: String,
+}
+
+/// The key is only ever shown in this response.
+#[derive(Debug, Serialize)]
+pub struct CreatedKey {
+	id: u32,
+	name: String,
+	key: String,
+}
+
+#[axum::debug_handler]
+pub async fn post(
+	_: Admin,
+	State(state): State<APIState>,
+	Json(body): Json<Body>,
+) -> Response<CreatedKey> {
+	trace!("POST /api/keys");
+	trace!("{body:?}");
+
+	let key = generate_secret();
+
+	let result = sqlx::query("INSERT INTO api_keys (name, key_hash) VALUES (?, ?)")
+		.bind(&body.name)
+		.bind(hash_key(&key))
+		.execute(state.db())
+		.await?;
+
+	let id = result.last_insert_id() as u32;
+
+	info!("Issued API key #{id} for `{}`.", body.name);
+
+	Ok(CreatedKey { id, name: body.name, key }.into())
+}
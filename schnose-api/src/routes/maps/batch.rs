@@ -0,0 +1,101 @@
+use {
+	crate::{batch, response::Response, state::APIState},
+	axum::{extract::State, Json},
+	gokz_rs::MapIdentifier,
+	itertools::{Either, Itertools},
+	schnose_api::{
+		error::{yeet, Error, Result},
+		models::{Map, MapQuery},
+	},
+	serde::Deserialize,
+	sqlx::QueryBuilder,
+	tracing::{debug, trace},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Body {
+	/// Map IDs or exact map names.
+	ids: Vec<MapIdentifier>,
+}
+
+/// The maps matching the given identifiers, in the order they were asked for. Unknown maps are
+/// left out.
+#[axum::debug_handler]
+pub async fn post(State(state): State<APIState>, Json(body): Json<Body>) -> Response<Vec<Map>> {
+	trace!("POST /api/maps/batch");
+	trace!("{body:?}");
+
+	batch::check_size(body.ids.len())?;
+
+	let maps = fetch(&body.ids, &state).await?;
+
+	let maps = body
+		.ids
+		.iter()
+		.filter_map(|ident| {
+			maps.iter().find(|map| match ident {
+				MapIdentifier::ID(map_id) => map.id == *map_id,
+				MapIdentifier::Name(map_name) => map.name.eq_ignore_ascii_case(map_name),
+			})
+		})
+		.cloned()
+		.collect_vec();
+
+	if maps.is_empty() {
+		yeet!(Error::NoContent);
+	}
+
+	Ok(maps.into())
+}
+
+/// Fetches every map matching one of `idents`, in no particular order.
+async fn fetch(idents: &[MapIdentifier], state: &APIState) -> Result<Vec<Map>> {
+	let (ids, names): (Vec<_>, Vec<_>) = idents
+		.iter()
+		.partition_map(|map| match map {
+			MapIdentifier::ID(map_id) => Either::Left(*map_id),
+			MapIdentifier::Name(map_name) => Either::Right(map_name.as_str()),
+		});
+
+	let mut query = QueryBuilder::new(MapQuery::select(true, true));
+
+	query.push(" WHERE FALSE ");
+
+	if !ids.is_empty() {
+		query.push(" OR map.id IN (");
+
+		let mut separated = query.separated(", ");
+
+		for map_id in ids {
+			separated.push_bind(map_id);
+		}
+
+		query.push(")");
+	}
+
+	if !names.is_empty() {
+		query.push(" OR map.name IN (");
+
+		let mut separated = query.separated(", ");
+
+		for map_name in names {
+			separated.push_bind(map_name);
+		}
+
+		query.push(")");
+	}
+
+	query.push(" GROUP BY map.id ");
+
+	let maps: Vec<MapQuery> = query
+		.build_query_as()
+		.fetch_all(state.db())
+		.await?;
+
+	debug!("Maps:\n\t{maps:?}");
+
+	Ok(maps
+		.into_iter()
+		.map(Map::from)
+		.collect())
+}
@@ -1,11 +1,14 @@
 use {
-	crate::{response::Response, state::APIState},
+	crate::{
+		cache::Cache,
+		response::{Response, ResponseBody},
+		state::APIState,
+	},
 	axum::extract::{Path, State},
-	gokz_rs::{MapIdentifier, SteamID},
-	itertools::Itertools,
+	gokz_rs::MapIdentifier,
 	schnose_api::{
-		error::Error,
-		models::{Course, Map, MapQuery, Mapper},
+		error::{Error, Result},
+		models::{Map, MapQuery},
 	},
 	sqlx::QueryBuilder,
 	tracing::{debug, trace},
@@ -15,29 +18,28 @@ use {
 pub async fn get(Path(map): Path<MapIdentifier>, State(state): State<APIState>) -> Response<Map> {
 	trace!("GET /api/maps/{map:?}");
 
-	let mut query = QueryBuilder::new(
-		r#"
-		SELECT
-		  map.*,
-		  JSON_ARRAYAGG(
-		    JSON_OBJECT(
-		      "id",    course.id,
-		      "stage", course.stage,
-		      "tier",  course.tier
-		    )
-		  ) as courses,
-		  JSON_ARRAYAGG(
-		    JSON_OBJECT(
-		      "name",     player.name,
-		      "steam_id", mapper.mapper_id
-		    )
-		  ) as mappers
-		FROM maps AS map
-		JOIN courses AS course ON course.map_id = map.id
-		JOIN mappers AS mapper ON mapper.map_id = map.id
-		JOIN players AS player ON player.id = mapper.mapper_id
-		"#,
-	);
+	let cache_key = Cache::key(&format!("/api/maps/{map}"), None);
+
+	let map = match state.cache.get::<Map>(&cache_key) {
+		Some(map) => map,
+		None => {
+			let map = fetch(map, &state).await?;
+
+			state
+				.cache
+				.insert(cache_key, map.clone());
+
+			map
+		}
+	};
+
+	let last_modified = Some(map.updated_on);
+
+	Ok(ResponseBody::from(map).last_modified(last_modified))
+}
+
+async fn fetch(map: MapIdentifier, state: &APIState) -> Result<Map> {
+	let mut query = QueryBuilder::new(MapQuery::select(true, true));
 
 	match map {
 		MapIdentifier::ID(map_id) => {
@@ -64,40 +66,5 @@ pub async fn get(Path(map): Path<MapIdentifier>, State(state): State<APIState>)
 
 	debug!("Map:\n\t{map:?}");
 
-	// TODO: Do this in the database instead of here. It's fine in this case since there aren't that
-	// many maps anyway, but I would still like to do this properly.
-	let courses = map
-		.courses
-		.0
-		.into_iter()
-		.flat_map(Course::try_from)
-		.sorted_by(|a, b| a.id.cmp(&b.id))
-		.dedup_by(|a, b| a.id == b.id)
-		.collect();
-
-	// TODO: Do this in the database instead of here. It's fine in this case since there aren't that
-	// many maps anyway, but I would still like to do this properly.
-	let mappers = map
-		.mappers
-		.0
-		.into_iter()
-		.flat_map(Mapper::try_from)
-		.sorted_by(|a, b| a.steam_id.cmp(&b.steam_id))
-		.dedup_by(|a, b| a.steam_id == b.steam_id)
-		.collect_vec();
-
-	Ok(Map {
-		id: map.id,
-		name: map.name,
-		global: map.global,
-		filesize: map.filesize,
-		courses,
-		mappers,
-		approved_by: map
-			.approved_by
-			.and_then(|id| (id == 0).then_some(SteamID::from_id32(id))),
-		created_on: map.created_on,
-		updated_on: map.updated_on,
-	}
-	.into())
+	Ok(Map::from(map))
 }
@@ -0,0 +1,99 @@
+use {
+	crate::{
+		leaderboard::Leaderboard,
+		lookup,
+		response::{Response, ResponseBody},
+		state::APIState,
+	},
+	axum::extract::{Path, Query, State},
+	gokz_rs::{MapIdentifier, Mode, PlayerIdentifier},
+	schnose_api::{
+		error::{yeet, Error, Result},
+		models::{LeaderboardEntry, RuntypeFilter},
+		serde::deser_opt_timestamp,
+	},
+	serde::Deserialize,
+	sqlx::types::chrono::{DateTime, Utc},
+	tracing::{debug, trace},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Params {
+	mode: Option<Mode>,
+	stage: Option<u8>,
+	#[serde(flatten)]
+	filter: RuntypeFilter,
+	tickrate: Option<u8>,
+
+	#[serde(default, deserialize_with = "deser_opt_timestamp")]
+	as_of: Option<DateTime<Utc>>,
+
+	limit: Option<u16>,
+	offset: Option<u64>,
+
+	/// Only return this player and the `radius` players above and below them. Course leaderboards
+	/// are the only ones supporting this so far; the points ranking is still missing, see
+	/// `routes::hall_of_fame`.
+	around: Option<PlayerIdentifier>,
+	radius: Option<u8>,
+}
+
+#[axum::debug_handler]
+pub async fn get(
+	Path(map): Path<MapIdentifier>,
+	Query(params): Query<Params>,
+	State(state): State<APIState>,
+) -> Response<Vec<LeaderboardEntry>> {
+	trace!("GET /api/maps/{map:?}/leaderboard");
+	trace!("{params:?}");
+
+	let leaderboard = Leaderboard::resolve(
+		&map, params.stage, params.mode, params.filter.runtype, params.as_of, &state,
+	)
+	.await?
+	.tickrate(params.tickrate);
+
+	let entries = entries(&leaderboard, &params, &state).await?;
+
+	if entries.is_empty() {
+		yeet!(Error::NoContent);
+	}
+
+	debug!("Leaderboard:\n\t{entries:?}");
+
+	let last_modified = entries
+		.iter()
+		.map(|entry| entry.record.created_on)
+		.max();
+
+	Ok(ResponseBody::from(entries).last_modified(last_modified))
+}
+
+/// Either a window around `params.around` or a page starting at `params.offset`.
+async fn entries(
+	leaderboard: &Leaderboard,
+	params: &Params,
+	state: &APIState,
+) -> Result<Vec<LeaderboardEntry>> {
+	match &params.around {
+		Some(player) => {
+			let player = lookup::player(player, state).await?;
+			let radius = params.radius.unwrap_or(5).min(50);
+
+			leaderboard
+				.around(player.id, radius as u64, state)
+				.await
+		}
+		None => {
+			let limit = match params.limit.unwrap_or(100) {
+				0 => 1,
+				limit @ (1..=500) => limit,
+				501.. => 500,
+			};
+
+			leaderboard
+				.entries(params.offset.unwrap_or(0), limit as u64, state)
+				.await
+		}
+	}
+}
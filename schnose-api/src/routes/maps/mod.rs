@@ -1,3 +1,11 @@
 pub mod root;
 
+pub mod batch;
+
 pub mod ident;
+
+pub mod leaderboard;
+
+pub mod wr;
+
+pub mod wr_history;
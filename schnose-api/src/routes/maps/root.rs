@@ -1,19 +1,27 @@
 use {
-	crate::{response::Response, state::APIState},
-	axum::extract::{Query, State},
-	gokz_rs::SteamID,
-	itertools::Itertools,
+	crate::{
+		batch,
+		cache::Cache,
+		fields::FieldParams,
+		response::{Response, ResponseBody},
+		state::APIState,
+	},
+	axum::extract::{Query, RawQuery, State},
 	schnose_api::{
-		error::{yeet, Error},
-		models::{Course, Map, MapQuery, Mapper},
+		error::{yeet, Error, Result},
+		models::{Map, MapQuery},
+		serde::deser_opt_list,
 	},
 	serde::Deserialize,
-	sqlx::QueryBuilder,
+	sqlx::{MySql, QueryBuilder},
 	tracing::{debug, trace},
 };
 
 #[derive(Debug, Deserialize)]
 pub struct Params {
+	#[serde(default, deserialize_with = "deser_opt_list")]
+	ids: Option<Vec<u16>>,
+
 	name: Option<String>,
 	global: Option<bool>,
 	limit: Option<u16>,
@@ -21,51 +29,47 @@ pub struct Params {
 
 #[axum::debug_handler]
 pub async fn get(
+	RawQuery(raw_query): RawQuery,
 	Query(params): Query<Params>,
+	Query(fields): Query<FieldParams>,
 	State(state): State<APIState>,
 ) -> Response<Vec<Map>> {
 	trace!("GET /api/maps");
 	trace!("{params:?}");
+	trace!("{fields:?}");
 
-	let mut query = QueryBuilder::new(
-		r#"
-		SELECT
-		  map.*,
-		  JSON_ARRAYAGG(
-		    JSON_OBJECT(
-		      "id",    course.id,
-		      "stage", course.stage,
-		      "tier",  course.tier
-		    )
-		  ) as courses,
-		  JSON_ARRAYAGG(
-		    JSON_OBJECT(
-		      "name",     player.name,
-		      "steam_id", mapper.mapper_id
-		    )
-		  ) as mappers
-		FROM maps AS map
-		LEFT JOIN courses AS course ON course.map_id = map.id
-		LEFT JOIN mappers AS mapper ON mapper.map_id = map.id
-		LEFT JOIN players AS player ON player.id = mapper.mapper_id
-		"#,
-	);
-
-	let mut filter = " WHERE ";
-
-	if let Some(name) = params.name {
-		query
-			.push(" WHERE map.name LIKE ")
-			.push_bind(format!("%{name}%"));
-		filter = " AND ";
-	}
+	let cache_key = Cache::key("/api/maps", raw_query.as_deref());
 
-	if let Some(global) = params.global {
-		query
-			.push(filter)
-			.push(" map.global = ")
-			.push_bind(global as u8);
-	}
+	let maps = match state.cache.get::<Vec<Map>>(&cache_key) {
+		Some(maps) => maps,
+		None => {
+			let maps = fetch(&params, &fields, &state).await?;
+
+			if maps.is_empty() {
+				yeet!(Error::NoContent);
+			}
+
+			state
+				.cache
+				.insert(cache_key, maps.clone());
+
+			maps
+		}
+	};
+
+	let last_modified = maps
+		.iter()
+		.map(|map| map.updated_on)
+		.max();
+
+	Ok(ResponseBody::from(maps).last_modified(last_modified))
+}
+
+async fn fetch(params: &Params, fields: &FieldParams, state: &APIState) -> Result<Vec<Map>> {
+	let mut query =
+		QueryBuilder::new(MapQuery::select(fields.expands("courses"), fields.expands("mappers")));
+
+	push_filters(&mut query, params)?;
 
 	query
 		.push(" GROUP BY map.id ")
@@ -83,50 +87,40 @@ pub async fn get(
 
 	debug!("Maps:\n\t{maps:?}");
 
-	if maps.is_empty() {
-		yeet!(Error::NoContent);
+	Ok(maps
+		.into_iter()
+		.map(Map::from)
+		.collect())
+}
+
+fn push_filters(query: &mut QueryBuilder<'_, MySql>, params: &Params) -> Result<()> {
+	query.push(" WHERE TRUE ");
+
+	if let Some(ids) = &params.ids {
+		batch::check_size(ids.len())?;
+
+		query.push(" AND map.id IN (");
+
+		let mut separated = query.separated(", ");
+
+		for map_id in ids {
+			separated.push_bind(*map_id);
+		}
+
+		query.push(")");
 	}
 
-	let maps = maps
-		.into_iter()
-		.map(|map| {
-			// TODO: Do this in the database instead of here. It's fine in this case since there aren't that
-			// many maps anyway, but I would still like to do this properly.
-			let mappers = map
-				.mappers
-				.0
-				.into_iter()
-				.flat_map(Mapper::try_from)
-				.sorted_by(|a, b| a.steam_id.cmp(&b.steam_id))
-				.dedup_by(|a, b| a.steam_id == b.steam_id)
-				.collect();
-
-			// TODO: Do this in the database instead of here. It's fine in this case since there aren't that
-			// many maps anyway, but I would still like to do this properly.
-			let courses = map
-				.courses
-				.0
-				.into_iter()
-				.flat_map(Course::try_from)
-				.sorted_by(|a, b| a.id.cmp(&b.id))
-				.dedup_by(|a, b| a.id == b.id)
-				.collect();
-
-			Map {
-				id: map.id,
-				name: map.name,
-				global: map.global,
-				filesize: map.filesize,
-				courses,
-				mappers,
-				approved_by: map
-					.approved_by
-					.and_then(|id| (id == 0).then_some(SteamID::from_id32(id))),
-				created_on: map.created_on,
-				updated_on: map.updated_on,
-			}
-		})
-		.collect_vec();
+	if let Some(name) = &params.name {
+		query
+			.push(" AND map.name LIKE ")
+			.push_bind(format!("%{name}%"));
+	}
+
+	if let Some(global) = params.global {
+		query
+			.push(" AND map.global = ")
+			.push_bind(global as u8);
+	}
 
-	Ok(maps.into())
+	Ok(())
 }
@@ -0,0 +1,58 @@
+use {
+	crate::{
+		leaderboard::Leaderboard,
+		response::{Response, ResponseBody},
+		state::APIState,
+	},
+	axum::extract::{Path, Query, State},
+	gokz_rs::{MapIdentifier, Mode},
+	schnose_api::{
+		error::Error,
+		models::{LeaderboardEntry, RuntypeFilter},
+		serde::deser_opt_timestamp,
+	},
+	serde::Deserialize,
+	sqlx::types::chrono::{DateTime, Utc},
+	tracing::{debug, trace},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Params {
+	mode: Option<Mode>,
+	stage: Option<u8>,
+	#[serde(flatten)]
+	filter: RuntypeFilter,
+	tickrate: Option<u8>,
+
+	#[serde(default, deserialize_with = "deser_opt_timestamp")]
+	as_of: Option<DateTime<Utc>>,
+}
+
+#[axum::debug_handler]
+pub async fn get(
+	Path(map): Path<MapIdentifier>,
+	Query(params): Query<Params>,
+	State(state): State<APIState>,
+) -> Response<LeaderboardEntry> {
+	trace!("GET /api/maps/{map:?}/wr");
+	trace!("{params:?}");
+
+	let leaderboard = Leaderboard::resolve(
+		&map, params.stage, params.mode, params.filter.runtype, params.as_of, &state,
+	)
+	.await?
+	.tickrate(params.tickrate);
+
+	let wr = leaderboard
+		.entries(0, 1, &state)
+		.await?
+		.into_iter()
+		.next()
+		.ok_or(Error::NoContent)?;
+
+	debug!("WR:\n\t{wr:?}");
+
+	let last_modified = Some(wr.record.created_on);
+
+	Ok(ResponseBody::from(wr).last_modified(last_modified))
+}
@@ -0,0 +1,101 @@
+use {
+	crate::{
+		leaderboard::Leaderboard,
+		response::{Response, ResponseBody},
+		state::APIState,
+	},
+	axum::extract::{Path, Query, State},
+	gokz_rs::{MapIdentifier, Mode},
+	schnose_api::{
+		error::{yeet, Error},
+		models::{Record, RecordQuery, RuntypeFilter, WorldRecord},
+		serde::deser_opt_timestamp,
+	},
+	serde::Deserialize,
+	sqlx::{
+		types::chrono::{DateTime, Utc},
+		QueryBuilder,
+	},
+	tracing::{debug, trace},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Params {
+	mode: Option<Mode>,
+	stage: Option<u8>,
+	#[serde(flatten)]
+	filter: RuntypeFilter,
+	tickrate: Option<u8>,
+
+	#[serde(default, deserialize_with = "deser_opt_timestamp")]
+	as_of: Option<DateTime<Utc>>,
+}
+
+#[axum::debug_handler]
+pub async fn get(
+	Path(map): Path<MapIdentifier>,
+	Query(params): Query<Params>,
+	State(state): State<APIState>,
+) -> Response<Vec<WorldRecord>> {
+	trace!("GET /api/maps/{map:?}/wr-history");
+	trace!("{params:?}");
+
+	let leaderboard = Leaderboard::resolve(
+		&map, params.stage, params.mode, params.filter.runtype, params.as_of, &state,
+	)
+	.await?
+	.tickrate(params.tickrate);
+
+	let mut query = QueryBuilder::new(RecordQuery::SELECT);
+
+	query.push(" WHERE record.id IN (SELECT wr.id FROM ");
+
+	Leaderboard::push_wr_at_time(&mut query, "wr", |query| leaderboard.push_filter(query));
+
+	query
+		.push(")")
+		.push(" ORDER BY record.created_on, record.id ");
+
+	let records: Vec<RecordQuery> = query
+		.build_query_as()
+		.fetch_all(state.db())
+		.await?;
+
+	if records.is_empty() {
+		yeet!(Error::NoContent);
+	}
+
+	debug!("WR history:\n\t{records:?}");
+
+	let records = records
+		.into_iter()
+		.map(Record::try_from)
+		.collect::<Result<Vec<_>, _>>()?;
+
+	let until = params.as_of.unwrap_or_else(Utc::now);
+
+	let history = records
+		.iter()
+		.enumerate()
+		.map(|(idx, record)| {
+			let previous = idx
+				.checked_sub(1)
+				.map(|idx| &records[idx]);
+			let next = records.get(idx + 1);
+
+			WorldRecord {
+				record: record.clone(),
+				improvement: previous.map(|previous| previous.time - record.time),
+				beaten_by: next.map(|next| next.id),
+				stood_for: (next.map_or(until, |next| next.created_on) - record.created_on)
+					.num_seconds(),
+			}
+		})
+		.collect::<Vec<_>>();
+
+	let last_modified = history
+		.last()
+		.map(|wr| wr.record.created_on);
+
+	Ok(ResponseBody::from(history).last_modified(last_modified))
+}
@@ -7,3 +7,13 @@ pub mod maps;
 pub mod servers;
 
 pub mod records;
+
+pub mod cache;
+
+pub mod keys;
+
+pub mod webhooks;
+
+pub mod courses;
+
+pub mod hall_of_fame;
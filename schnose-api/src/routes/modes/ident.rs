@@ -1,5 +1,5 @@
 use {
-	crate::{response::Response, state::APIState},
+	crate::{cache::Cache, response::Response, state::APIState},
 	axum::extract::{Path, State},
 	schnose_api::{error::Error, models::Mode},
 	schnosedb::models::ModeRow,
@@ -10,6 +10,12 @@ use {
 pub async fn get(Path(mode): Path<gokz_rs::Mode>, State(state): State<APIState>) -> Response<Mode> {
 	trace!("GET /api/modes/{:?}", mode.api());
 
+	let cache_key = Cache::key(&format!("/api/modes/{}", mode as u8), None);
+
+	if let Some(mode) = state.cache.get::<Mode>(&cache_key) {
+		return Ok(mode.into());
+	}
+
 	let mode: ModeRow = sqlx::query_as("SELECT * FROM modes WHERE id = ?")
 		.bind(mode as u16)
 		.fetch_optional(state.db())
@@ -18,5 +24,11 @@ pub async fn get(Path(mode): Path<gokz_rs::Mode>, State(state): State<APIState>)
 
 	debug!("Mode:\n\t{mode:?}");
 
-	Ok(Mode::from(mode).into())
+	let mode = Mode::from(mode);
+
+	state
+		.cache
+		.insert(cache_key, mode.clone());
+
+	Ok(mode.into())
 }
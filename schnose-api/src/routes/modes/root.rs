@@ -1,5 +1,5 @@
 use {
-	crate::{response::Response, state::APIState},
+	crate::{cache::Cache, response::Response, state::APIState},
 	axum::extract::State,
 	itertools::Itertools,
 	schnose_api::models::Mode,
@@ -11,16 +11,27 @@ use {
 pub async fn get(State(state): State<APIState>) -> Response<Vec<Mode>> {
 	trace!("GET /api/modes");
 
+	let cache_key = Cache::key("/api/modes", None);
+
+	if let Some(modes) = state.cache.get::<Vec<Mode>>(&cache_key) {
+		return Ok(modes.into());
+	}
+
 	let modes: Vec<ModeRow> = sqlx::query_as("SELECT * FROM modes")
 		.fetch_all(state.db())
 		.await?;
 
 	debug!("Modes:\n\t{modes:?}");
 
-	Ok(modes
+	let modes = modes
 		.into_iter()
 		.map(Into::into)
 		.inspect(|mode| debug!("Parsed mode: {mode:?}"))
-		.collect_vec()
-		.into())
+		.collect_vec();
+
+	state
+		.cache
+		.insert(cache_key, modes.clone());
+
+	Ok(modes.into())
 }
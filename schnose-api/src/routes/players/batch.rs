@@ -0,0 +1,62 @@
+use {
+	crate::{batch, response::Response, state::APIState},
+	axum::{extract::State, Json},
+	gokz_rs::SteamID,
+	schnose_api::{
+		error::{yeet, Error, Result},
+		models::Player,
+	},
+	schnosedb::models::PlayerRow,
+	serde::Deserialize,
+	sqlx::QueryBuilder,
+	tracing::{debug, trace},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Body {
+	ids: Vec<SteamID>,
+}
+
+/// The players with the given SteamIDs, in the order they were asked for. Unknown SteamIDs are
+/// left out.
+#[axum::debug_handler]
+pub async fn post(State(state): State<APIState>, Json(body): Json<Body>) -> Response<Vec<Player>> {
+	trace!("POST /api/players/batch");
+	trace!("{body:?}");
+
+	batch::check_size(body.ids.len())?;
+
+	let mut query = QueryBuilder::new("SELECT * FROM players WHERE id IN (");
+	let mut separated = query.separated(", ");
+
+	for steam_id in &body.ids {
+		separated.push_bind(steam_id.as_id32());
+	}
+
+	query.push(")");
+
+	let players: Vec<PlayerRow> = query
+		.build_query_as()
+		.fetch_all(state.db())
+		.await?;
+
+	debug!("Players:\n\t{players:?}");
+
+	let players = body
+		.ids
+		.iter()
+		.filter_map(|steam_id| {
+			players
+				.iter()
+				.find(|player| player.id == steam_id.as_id32())
+		})
+		.cloned()
+		.map(Player::try_from)
+		.collect::<Result<Vec<_>>>()?;
+
+	if players.is_empty() {
+		yeet!(Error::NoContent);
+	}
+
+	Ok(players.into())
+}
@@ -0,0 +1,147 @@
+use {
+	crate::{lookup, response::Response, state::APIState},
+	axum::extract::{Query, State},
+	gokz_rs::{Mode, PlayerIdentifier},
+	schnose_api::{
+		error::{yeet, Error, Result},
+		models::{
+			Completion, CompletionQuery, Player, PlayerComparison, RuntypeFilter, SharedCompletion,
+		},
+	},
+	serde::Deserialize,
+	std::{
+		cmp::Ordering,
+		collections::{HashMap, HashSet},
+	},
+	tracing::{debug, trace},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Params {
+	a: PlayerIdentifier,
+	b: PlayerIdentifier,
+	mode: Option<Mode>,
+	#[serde(flatten)]
+	filter: RuntypeFilter,
+}
+
+#[axum::debug_handler]
+pub async fn get(
+	Query(params): Query<Params>,
+	State(state): State<APIState>,
+) -> Response<PlayerComparison> {
+	trace!("GET /api/players/compare");
+	trace!("{params:?}");
+
+	let a = lookup::player(&params.a, &state).await?;
+	let b = lookup::player(&params.b, &state).await?;
+
+	if a.id == b.id {
+		yeet!(Error::InvalidInput("`a` and `b` must be different players."));
+	}
+
+	let mode = params.mode.unwrap_or(Mode::KZTimer);
+	let runtype = params
+		.filter
+		.runtype
+		.unwrap_or_default();
+
+	let completions: Vec<CompletionQuery> = sqlx::query_as(
+		r#"
+		SELECT
+		  pb.player_id,
+		  map.id AS map_id,
+		  map.name AS map_name,
+		  course.id AS course_id,
+		  course.stage,
+		  course.tier,
+		  pb.time
+		FROM (
+		  SELECT
+		    r.player_id,
+		    r.course_id,
+		    MIN(r.time) AS time
+		  FROM records AS r
+		  WHERE r.player_id IN (?, ?)
+		    AND r.mode_id = ?
+		    AND (r.teleports > 0) = ?
+		    AND r.status = "valid"
+		  GROUP BY r.player_id, r.course_id
+		) AS pb
+		JOIN courses AS course ON course.id = pb.course_id
+		JOIN maps AS map ON map.id = course.map_id
+		ORDER BY map.name, course.stage
+		"#,
+	)
+	.bind(a.id)
+	.bind(b.id)
+	.bind(mode as u8)
+	.bind(runtype.has_teleports())
+	.fetch_all(state.db())
+	.await?;
+
+	debug!("Completions:\n\t{completions:?}");
+
+	let mut comparison = PlayerComparison {
+		a: Player::try_from(a)?,
+		b: Player::try_from(b)?,
+		mode,
+		runtype,
+		wins: 0,
+		losses: 0,
+		ties: 0,
+		shared: Vec::new(),
+		only_a: Vec::new(),
+		only_b: Vec::new(),
+	};
+
+	merge(&mut comparison, completions)?;
+
+	Ok(comparison.into())
+}
+
+/// Sorts both players' completions into [`PlayerComparison`]'s buckets, keeping their order.
+fn merge(comparison: &mut PlayerComparison, completions: Vec<CompletionQuery>) -> Result<()> {
+	let a_id = comparison.a.steam_id.as_id32();
+
+	let a_courses = completions
+		.iter()
+		.filter(|completion| completion.player_id == a_id)
+		.map(|completion| completion.course_id)
+		.collect::<HashSet<_>>();
+
+	let b_times = completions
+		.iter()
+		.filter(|completion| completion.player_id != a_id)
+		.map(|completion| (completion.course_id, completion.time))
+		.collect::<HashMap<_, _>>();
+
+	for completion in completions {
+		let is_a = completion.player_id == a_id;
+		let completion = Completion::try_from(completion)?;
+
+		if !is_a {
+			if !a_courses.contains(&completion.course_id) {
+				comparison.only_b.push(completion);
+			}
+			continue;
+		}
+
+		let Some(&b_time) = b_times.get(&completion.course_id) else {
+			comparison.only_a.push(completion);
+			continue;
+		};
+
+		let shared = SharedCompletion::new(completion, b_time);
+
+		match shared.a_time.total_cmp(&shared.b_time) {
+			Ordering::Less => comparison.wins += 1,
+			Ordering::Greater => comparison.losses += 1,
+			Ordering::Equal => comparison.ties += 1,
+		}
+
+		comparison.shared.push(shared);
+	}
+
+	Ok(())
+}
@@ -0,0 +1,154 @@
+use {
+	crate::{
+		leaderboard::Leaderboard,
+		lookup,
+		response::{Response, ResponseBody},
+		state::APIState,
+	},
+	axum::extract::{Path, Query, State},
+	gokz_rs::{MapIdentifier, Mode, PlayerIdentifier},
+	schnose_api::{
+		error::{yeet, Error, Result},
+		models::{PersonalBest, Record, RuntypeFilter},
+	},
+	serde::Deserialize,
+	sqlx::{FromRow, QueryBuilder},
+	tracing::{debug, trace},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Params {
+	map: MapIdentifier,
+	stage: Option<u8>,
+	mode: Option<Mode>,
+	#[serde(flatten)]
+	filter: RuntypeFilter,
+	tickrate: Option<u8>,
+}
+
+#[derive(Debug, FromRow)]
+struct Improvement {
+	id: u32,
+	previous_best: Option<f64>,
+	time: f64,
+	placement: i64,
+}
+
+impl Improvement {
+	fn into_personal_best(self, record: Record) -> PersonalBest {
+		PersonalBest {
+			record,
+			improvement: self
+				.previous_best
+				.map(|previous_best| previous_best - self.time),
+			rank: self.placement as u32,
+		}
+	}
+}
+
+#[axum::debug_handler]
+pub async fn get(
+	Path(player): Path<PlayerIdentifier>,
+	Query(params): Query<Params>,
+	State(state): State<APIState>,
+) -> Response<Vec<PersonalBest>> {
+	trace!("GET /api/players/{player:?}/history");
+	trace!("{params:?}");
+
+	let player = lookup::player(&player, &state).await?;
+	let leaderboard = Leaderboard::resolve(
+		&params.map, params.stage, params.mode, params.filter.runtype, None, &state,
+	)
+	.await?
+	.tickrate(params.tickrate);
+
+	let improvements = improvements(player.id, &leaderboard, &state).await?;
+
+	if improvements.is_empty() {
+		yeet!(Error::NoContent);
+	}
+
+	let history = with_records(improvements, &state).await?;
+
+	let last_modified = history
+		.last()
+		.map(|pb| pb.record.created_on);
+
+	Ok(ResponseBody::from(history).last_modified(last_modified))
+}
+
+/// Every run that beat the player's previous best, and how many other players were faster at that
+/// point.
+async fn improvements(
+	player_id: u32,
+	leaderboard: &Leaderboard,
+	state: &APIState,
+) -> Result<Vec<Improvement>> {
+	let mut query = QueryBuilder::new(
+		r#"
+		SELECT
+		  improvement.id,
+		  improvement.previous_best,
+		  improvement.time,
+		  1 + (
+		    SELECT COUNT(DISTINCT other.player_id)
+		    FROM records AS other
+		    WHERE other.course_id = improvement.course_id
+		      AND other.mode_id = improvement.mode_id
+		      AND (other.teleports > 0) = (improvement.teleports > 0)
+		      AND other.status = "valid"
+		      AND other.player_id != improvement.player_id
+		      AND other.created_on <= improvement.created_on
+		      AND other.time < improvement.time
+		"#,
+	);
+
+	if let Some(tickrate) = leaderboard.tickrate {
+		query
+			.push(" AND other.tickrate = ")
+			.push_bind(tickrate);
+	}
+
+	query.push(" ) AS placement FROM ");
+
+	Leaderboard::push_wr_at_time(&mut query, "improvement", |query| {
+		leaderboard.push_filter(query);
+
+		query
+			.push(" AND r.player_id = ")
+			.push_bind(player_id);
+	});
+
+	query.push(" ORDER BY improvement.created_on, improvement.id ");
+
+	let improvements = query
+		.build_query_as()
+		.fetch_all(state.db())
+		.await?;
+
+	debug!("Improvements:\n\t{improvements:?}");
+
+	Ok(improvements)
+}
+
+/// Fetches the full record for every improvement, keeping their order.
+async fn with_records(
+	improvements: Vec<Improvement>,
+	state: &APIState,
+) -> Result<Vec<PersonalBest>> {
+	let ids = improvements
+		.iter()
+		.map(|improvement| improvement.id)
+		.collect::<Vec<_>>();
+
+	let mut records = lookup::records(&ids, state).await?;
+
+	Ok(improvements
+		.into_iter()
+		.filter_map(|improvement| {
+			records
+				.remove(&improvement.id)
+				.map(|record| improvement.into_personal_best(record))
+		})
+		.collect())
+}
@@ -1,9 +1,8 @@
 use {
-	crate::{response::Response, state::APIState},
+	crate::{lookup, response::Response, state::APIState},
 	axum::extract::{Path, State},
 	gokz_rs::PlayerIdentifier,
-	schnose_api::{error::Error, models::Player},
-	schnosedb::models::PlayerRow,
+	schnose_api::models::Player,
 	tracing::{debug, trace},
 };
 
@@ -14,20 +13,7 @@ pub async fn get(
 ) -> Response<Player> {
 	trace!("GET /api/players/{player:?}");
 
-	let query = match player {
-		PlayerIdentifier::SteamID(steam_id) => {
-			sqlx::query_as("SELECT * FROM players WHERE id = ? LIMIT 1").bind(steam_id.as_id32())
-		}
-		PlayerIdentifier::Name(player_name) => {
-			sqlx::query_as("SELECT * FROM players WHERE name LIKE ? LIMIT 1")
-				.bind(format!("%{player_name}%"))
-		}
-	};
-
-	let player: PlayerRow = query
-		.fetch_optional(state.db())
-		.await?
-		.ok_or(Error::NoContent)?;
+	let player = lookup::player(&player, &state).await?;
 
 	debug!("Player:\n\t{player:?}");
 
@@ -1,3 +1,13 @@
 pub mod root;
 
 pub mod ident;
+
+pub mod compare;
+
+pub mod batch;
+
+pub mod history;
+
+pub mod names;
+
+pub mod recommendations;
@@ -0,0 +1,54 @@
+use {
+	crate::{
+		lookup,
+		response::{Response, ResponseBody},
+		state::APIState,
+	},
+	axum::extract::{Path, State},
+	gokz_rs::PlayerIdentifier,
+	itertools::Itertools,
+	schnose_api::{
+		error::{yeet, Error},
+		models::PlayerName,
+	},
+	schnosedb::models::PlayerNameRow,
+	tracing::{debug, trace},
+};
+
+/// Every name the player has been seen with, most recent first.
+#[axum::debug_handler]
+pub async fn get(
+	Path(player): Path<PlayerIdentifier>,
+	State(state): State<APIState>,
+) -> Response<Vec<PlayerName>> {
+	trace!("GET /api/players/{player:?}/names");
+
+	let player = lookup::player(&player, &state).await?;
+
+	let names: Vec<PlayerNameRow> = sqlx::query_as(
+		r#"
+		SELECT *
+		FROM player_names
+		WHERE player_id = ?
+		ORDER BY last_seen DESC, first_seen DESC
+		"#,
+	)
+	.bind(player.id)
+	.fetch_all(state.db())
+	.await?;
+
+	if names.is_empty() {
+		yeet!(Error::NoContent);
+	}
+
+	debug!("Names:\n\t{names:?}");
+
+	let names = names
+		.into_iter()
+		.map(PlayerName::from)
+		.collect_vec();
+
+	let last_modified = names.first().map(|name| name.last_seen);
+
+	Ok(ResponseBody::from(names).last_modified(last_modified))
+}
@@ -0,0 +1,187 @@
+use {
+	crate::{lookup, response::Response, state::APIState},
+	axum::extract::{Path, Query, State},
+	gokz_rs::{Mode, PlayerIdentifier, Tier},
+	schnose_api::{
+		error::{yeet, Error, Result},
+		models::{
+			Player, Recommendation, RecommendationQuery, Recommendations, TierCompletions,
+			TierCompletionsQuery,
+		},
+	},
+	serde::Deserialize,
+	tracing::{debug, trace},
+};
+
+/// How many players with the most overlapping completions are taken into account.
+const SIMILAR_PLAYERS: u8 = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct Params {
+	mode: Option<Mode>,
+	limit: Option<u8>,
+}
+
+#[axum::debug_handler]
+pub async fn get(
+	Path(player): Path<PlayerIdentifier>,
+	Query(params): Query<Params>,
+	State(state): State<APIState>,
+) -> Response<Recommendations> {
+	trace!("GET /api/players/{player:?}/recommendations");
+	trace!("{params:?}");
+
+	let player = lookup::player(&player, &state).await?;
+	let mode = params.mode.unwrap_or(Mode::KZTimer);
+
+	let completions = tier_completions(player.id, mode, &state).await?;
+	let comfort_tier = comfort_tier(&completions);
+	let limit = match params.limit.unwrap_or(10) {
+		0 => 1,
+		limit @ (1..=50) => limit,
+		51.. => 50,
+	};
+
+	let courses = recommend(player.id, mode, comfort_tier, limit, &state).await?;
+
+	if courses.is_empty() {
+		yeet!(Error::NoContent);
+	}
+
+	Ok(Recommendations {
+		player: Player::try_from(player)?,
+		mode,
+		comfort_tier: comfort_tier
+			.try_into()
+			.expect("Comfort tier is always a valid tier."),
+		completions,
+		courses,
+	}
+	.into())
+}
+
+async fn tier_completions(
+	player_id: u32,
+	mode: Mode,
+	state: &APIState,
+) -> Result<Vec<TierCompletions>> {
+	let completions: Vec<TierCompletionsQuery> = sqlx::query_as(
+		r#"
+		SELECT
+		  course.tier,
+		  COUNT(DISTINCT completed.course_id) AS completed,
+		  COUNT(DISTINCT course.id) AS total
+		FROM courses AS course
+		JOIN filters AS filter ON filter.course_id = course.id AND filter.mode_id = ?
+		LEFT JOIN (
+		  SELECT DISTINCT r.course_id
+		  FROM records AS r
+		  WHERE r.player_id = ?
+		    AND r.mode_id = ?
+		    AND r.status = "valid"
+		) AS completed ON completed.course_id = course.id
+		GROUP BY course.tier
+		ORDER BY course.tier
+		"#,
+	)
+	.bind(mode as u8)
+	.bind(player_id)
+	.bind(mode as u8)
+	.fetch_all(state.db())
+	.await?;
+
+	debug!("Completions per tier:\n\t{completions:?}");
+
+	completions
+		.into_iter()
+		.map(TierCompletions::try_from)
+		.collect()
+}
+
+/// The highest tier where the player completed at least 3 courses or a quarter of all courses.
+/// Players without any such tier start at tier 1.
+fn comfort_tier(completions: &[TierCompletions]) -> u8 {
+	completions
+		.iter()
+		.filter(|tier| tier.completed >= 3 || tier.completed * 4 >= tier.total.max(1))
+		.map(|tier| tier.tier as u8)
+		.max()
+		.unwrap_or(Tier::VeryEasy as u8)
+}
+
+/// Unfinished global courses at the player's comfort tier or one above, ranked by how many of
+/// the most similar players (those sharing the most completions) finished them.
+async fn recommend(
+	player_id: u32,
+	mode: Mode,
+	comfort_tier: u8,
+	limit: u8,
+	state: &APIState,
+) -> Result<Vec<Recommendation>> {
+	let recommendations: Vec<RecommendationQuery> = sqlx::query_as(
+		r#"
+		WITH completed AS (
+		  SELECT DISTINCT r.course_id
+		  FROM records AS r
+		  WHERE r.player_id = ?
+		    AND r.mode_id = ?
+		    AND r.status = "valid"
+		), similar AS (
+		  SELECT
+		    r.player_id,
+		    COUNT(DISTINCT r.course_id) AS shared
+		  FROM records AS r
+		  JOIN completed ON completed.course_id = r.course_id
+		  WHERE r.player_id != ?
+		    AND r.mode_id = ?
+		    AND r.status = "valid"
+		  GROUP BY r.player_id
+		  ORDER BY shared DESC
+		  LIMIT ?
+		)
+		SELECT
+		  map.id AS map_id,
+		  map.name AS map_name,
+		  course.id AS course_id,
+		  course.stage,
+		  course.tier,
+		  COUNT(DISTINCT similar_completion.player_id) AS similar_players
+		FROM courses AS course
+		JOIN maps AS map ON map.id = course.map_id
+		JOIN filters AS filter ON filter.course_id = course.id AND filter.mode_id = ?
+		LEFT JOIN (
+		  SELECT DISTINCT
+		    r.course_id,
+		    r.player_id
+		  FROM records AS r
+		  JOIN similar ON similar.player_id = r.player_id
+		  WHERE r.mode_id = ?
+		    AND r.status = "valid"
+		) AS similar_completion ON similar_completion.course_id = course.id
+		WHERE course.id NOT IN (SELECT course_id FROM completed)
+		  AND course.tier BETWEEN ? AND ?
+		GROUP BY course.id
+		ORDER BY similar_players DESC, course.tier, map.name, course.stage
+		LIMIT ?
+		"#,
+	)
+	.bind(player_id)
+	.bind(mode as u8)
+	.bind(player_id)
+	.bind(mode as u8)
+	.bind(SIMILAR_PLAYERS)
+	.bind(mode as u8)
+	.bind(mode as u8)
+	.bind(comfort_tier)
+	.bind((comfort_tier + 1).min(Tier::Death as u8))
+	.bind(limit)
+	.fetch_all(state.db())
+	.await?;
+
+	debug!("Recommendations:\n\t{recommendations:?}");
+
+	recommendations
+		.into_iter()
+		.map(Recommendation::try_from)
+		.collect()
+}
@@ -1,16 +1,22 @@
 use {
-	crate::{response::Response, state::APIState},
+	crate::{batch, response::Response, state::APIState},
 	axum::extract::{Query, State},
+	gokz_rs::SteamID,
 	itertools::Itertools,
-	schnose_api::models::Player,
+	schnose_api::{error::Result, models::Player, serde::deser_opt_list},
 	schnosedb::models::PlayerRow,
 	serde::Deserialize,
-	sqlx::QueryBuilder,
+	sqlx::{MySql, QueryBuilder},
 	tracing::{debug, trace},
 };
 
 #[derive(Debug, Deserialize)]
 pub struct Params {
+	#[serde(default, deserialize_with = "deser_opt_list")]
+	ids: Option<Vec<SteamID>>,
+
+	/// Matches current and past names.
+	name: Option<String>,
 	is_banned: Option<bool>,
 	limit: Option<u16>,
 	offset: Option<i64>,
@@ -26,11 +32,7 @@ pub async fn get(
 
 	let mut query = QueryBuilder::new("SELECT * FROM players WHERE id > 0");
 
-	if let Some(is_banned) = params.is_banned {
-		query
-			.push(" AND is_banned = ")
-			.push_bind(is_banned as u8);
-	}
+	push_filters(&mut query, &params)?;
 
 	query
 		.push(" LIMIT ")
@@ -58,3 +60,38 @@ pub async fn get(
 		.collect_vec()
 		.into())
 }
+
+fn push_filters(query: &mut QueryBuilder<'_, MySql>, params: &Params) -> Result<()> {
+	if let Some(ids) = &params.ids {
+		batch::check_size(ids.len())?;
+
+		query.push(" AND id IN (");
+
+		let mut separated = query.separated(", ");
+
+		for steam_id in ids {
+			separated.push_bind(steam_id.as_id32());
+		}
+
+		query.push(")");
+	}
+
+	if let Some(name) = &params.name {
+		let name = format!("%{name}%");
+
+		query
+			.push(" AND (name LIKE ")
+			.push_bind(name.clone())
+			.push(" OR id IN (SELECT player_id FROM player_names WHERE name LIKE ")
+			.push_bind(name)
+			.push(")) ");
+	}
+
+	if let Some(is_banned) = params.is_banned {
+		query
+			.push(" AND is_banned = ")
+			.push_bind(is_banned as u8);
+	}
+
+	Ok(())
+}
@@ -0,0 +1,42 @@
+use {
+	crate::{batch, lookup, response::Response, state::APIState},
+	axum::{extract::State, Json},
+	itertools::Itertools,
+	schnose_api::{
+		error::{yeet, Error},
+		models::Record,
+	},
+	serde::Deserialize,
+	tracing::{debug, trace},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Body {
+	ids: Vec<u32>,
+}
+
+/// The records with the given IDs, in the order they were asked for. Unknown IDs are left out.
+#[axum::debug_handler]
+pub async fn post(State(state): State<APIState>, Json(body): Json<Body>) -> Response<Vec<Record>> {
+	trace!("POST /api/records/batch");
+	trace!("{body:?}");
+
+	batch::check_size(body.ids.len())?;
+
+	let records = lookup::records(&body.ids, &state).await?;
+
+	debug!("Records:\n\t{records:?}");
+
+	let records = body
+		.ids
+		.iter()
+		.filter_map(|id| records.get(id))
+		.cloned()
+		.collect_vec();
+
+	if records.is_empty() {
+		yeet!(Error::NoContent);
+	}
+
+	Ok(records.into())
+}
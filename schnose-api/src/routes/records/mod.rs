@@ -1 +1,7 @@
 pub mod root;
+
+pub mod batch;
+
+pub mod status;
+
+pub mod stream;
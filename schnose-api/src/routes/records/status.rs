@@ -0,0 +1,72 @@
+use {
+	crate::{auth::Admin, response::Response, state::APIState},
+	axum::{
+		extract::{Path, State},
+		Json,
+	},
+	gokz_rs::SteamID,
+	schnose_api::{
+		error::{yeet, Error},
+		models::{Record, RecordQuery},
+	},
+	schnosedb::models::RecordStatus,
+	serde::Deserialize,
+	sqlx::QueryBuilder,
+	tracing::{debug, trace},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Body {
+	status: RecordStatus,
+	reason: Option<String>,
+	moderator: SteamID,
+}
+
+#[axum::debug_handler]
+pub async fn put(
+	_: Admin,
+	Path(record_id): Path<u32>,
+	State(state): State<APIState>,
+	Json(body): Json<Body>,
+) -> Response<Record> {
+	trace!("PUT /api/records/{record_id}/status");
+	trace!("{body:?}");
+
+	let result = sqlx::query(
+		r#"
+		UPDATE records
+		SET
+		  status = ?,
+		  status_reason = ?,
+		  moderated_by = ?
+		WHERE id = ?
+		"#,
+	)
+	.bind(body.status)
+	.bind(&body.reason)
+	.bind(body.moderator.as_id32())
+	.bind(record_id)
+	.execute(state.db())
+	.await?;
+
+	if result.rows_affected() == 0 {
+		yeet!(Error::NoContent);
+	}
+
+	state.cache.invalidate();
+
+	let mut query = QueryBuilder::new(RecordQuery::SELECT);
+
+	query
+		.push(" WHERE record.id = ")
+		.push_bind(record_id);
+
+	let record: RecordQuery = query
+		.build_query_as()
+		.fetch_one(state.db())
+		.await?;
+
+	debug!("Record:\n\t{record:?}");
+
+	Ok(Record::try_from(record)?.into())
+}
@@ -0,0 +1,43 @@
+use {
+	crate::state::APIState,
+	axum::{
+		extract::{Query, State},
+		response::sse::{Event, KeepAlive, Sse},
+	},
+	schnose_api::{
+		models::{RecordEvent, RecordFilter},
+		serde::{OutputFormat, OUTPUT_FORMAT},
+	},
+	tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt},
+	tracing::trace,
+};
+
+/// Server-Sent Events feed of newly inserted records. Every event is called `record` and carries
+/// a JSON [`RecordEvent`].
+#[axum::debug_handler]
+pub async fn get(
+	Query(filter): Query<RecordFilter>,
+	State(state): State<APIState>,
+) -> Sse<impl Stream<Item = Result<Event, serde_json::Error>>> {
+	trace!("GET /api/records/stream");
+	trace!("{filter:?}");
+
+	// Events are serialized after this handler returned, outside of the request's scope.
+	let output_format = OutputFormat::current();
+
+	let events = BroadcastStream::new(state.record_events.subscribe()).filter_map(
+		move |event: Result<RecordEvent, _>| {
+			// Lagging subscribers just miss a few events.
+			let event = event.ok()?;
+			filter.matches(&event).then(|| {
+				OUTPUT_FORMAT.sync_scope(output_format, || {
+					Event::default()
+						.event("record")
+						.json_data(&event)
+				})
+			})
+		},
+	);
+
+	Sse::new(events).keep_alive(KeepAlive::default())
+}
@@ -20,15 +20,23 @@ pub async fn get(
 	let mut query = QueryBuilder::new(
 		r#"
 		SELECT
-		  server.*,
+		  server.id,
+		  server.name,
+		  server.ip,
+		  server.port,
+		  server.tickrate,
 		  JSON_OBJECT(
 		    "name",     owner.name,
-		    "steam_id", owner.id,
+		    "steam_id", owner.id
 		  ) AS owned_by,
-		  approver.id AS approved_by
+		  JSON_OBJECT(
+		    "steam_id",  server.approved_by,
+		    "name",      approver.name,
+		    "is_banned", approver.is_banned
+		  ) AS approved_by
 		FROM servers AS server
-		LEFT JOIN players AS owner ON player.id = server.owned_by
-		LEFT JOIN players AS approver ON player.id = server.approved_by
+		LEFT JOIN players AS owner ON owner.id = server.owned_by
+		LEFT JOIN players AS approver ON approver.id = server.approved_by
 		"#,
 	);
 
@@ -1,3 +1,52 @@
+use {
+	crate::leaderboard::Leaderboard,
+	gokz_rs::Mode,
+	schnose_api::models::RuntypeFilter,
+	sqlx::{MySql, QueryBuilder},
+};
+
 pub mod root;
 
 pub mod ident;
+
+pub mod records;
+
+pub mod stats;
+
+pub mod status;
+
+pub mod online;
+
+/// Pushes the records set on `server_id` that were the WR at the time they were set, aliased
+/// `wr`. Only courses with a filter for the record's mode count.
+fn push_wrs(
+	query: &mut QueryBuilder<'_, MySql>,
+	server_id: u16,
+	mode: Option<Mode>,
+	filter: RuntypeFilter,
+) {
+	Leaderboard::push_wr_at_time(query, "wr", |query| {
+		query
+			.push(
+				r#"
+				JOIN filters AS filter ON filter.course_id = r.course_id AND filter.mode_id = r.mode_id
+				WHERE r.status = "valid"
+				  AND r.course_id IN (SELECT DISTINCT course_id FROM records WHERE server_id =
+				"#,
+			)
+			.push_bind(server_id)
+			.push(")");
+
+		if let Some(mode) = mode {
+			query
+				.push(" AND r.mode_id = ")
+				.push_bind(mode as u8);
+		}
+
+		filter.push(query, "r");
+	});
+
+	query
+		.push(" WHERE wr.server_id = ")
+		.push_bind(server_id);
+}
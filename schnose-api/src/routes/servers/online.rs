@@ -0,0 +1,56 @@
+use {
+	crate::{response::Response, state::APIState},
+	axum::extract::{Query, State},
+	schnose_api::{
+		error::{yeet, Error},
+		models::ServerStatus,
+	},
+	serde::Deserialize,
+	tracing::{debug, trace},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Params {
+	map: Option<String>,
+	min_players: Option<u8>,
+}
+
+/// Every server that answered the last A2S query, most populated first.
+#[axum::debug_handler]
+pub async fn get(
+	Query(params): Query<Params>,
+	State(state): State<APIState>,
+) -> Response<Vec<ServerStatus>> {
+	trace!("GET /api/servers/online");
+	trace!("{params:?}");
+
+	let mut servers = state
+		.server_status
+		.read()
+		.expect("Server status lock should never be poisoned.")
+		.values()
+		.filter(|status| status.player_count >= params.min_players.unwrap_or(0))
+		.filter(|status| match &params.map {
+			Some(map) => status
+				.map
+				.to_lowercase()
+				.contains(&map.to_lowercase()),
+			None => true,
+		})
+		.cloned()
+		.collect::<Vec<_>>();
+
+	if servers.is_empty() {
+		yeet!(Error::NoContent);
+	}
+
+	servers.sort_by(|a, b| {
+		b.player_count
+			.cmp(&a.player_count)
+			.then(a.server_id.cmp(&b.server_id))
+	});
+
+	debug!("Online servers:\n\t{servers:?}");
+
+	Ok(servers.into())
+}
@@ -0,0 +1,137 @@
+use {
+	crate::{
+		fields::FieldParams,
+		format::{self, Format},
+		lookup,
+		response::{Response, ResponseBody},
+		state::APIState,
+	},
+	axum::{
+		extract::{Path, Query, State},
+		response::IntoResponse,
+		Extension,
+	},
+	gokz_rs::{Mode, ServerIdentifier},
+	schnose_api::{
+		error::{yeet, Error, Result},
+		models::{Record, RecordQuery, RuntypeFilter},
+	},
+	serde::Deserialize,
+	sqlx::{MySql, QueryBuilder},
+	tracing::{debug, trace},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Params {
+	mode: Option<Mode>,
+	#[serde(flatten)]
+	filter: RuntypeFilter,
+	tickrate: Option<u8>,
+	wr_only: Option<bool>,
+	limit: Option<u16>,
+	offset: Option<u64>,
+}
+
+/// Valid records set on a server, newest first. `?wr_only=true` narrows them down to the ones that
+/// were the WR at the time they were set. NDJSON is streamed, see [`format::stream_ndjson`].
+#[axum::debug_handler]
+pub async fn get(
+	Path(server): Path<ServerIdentifier>,
+	Query(params): Query<Params>,
+	Query(fields): Query<FieldParams>,
+	Extension(format): Extension<Format>,
+	State(state): State<APIState>,
+) -> Result<axum::response::Response> {
+	trace!("GET /api/servers/{server:?}/records");
+	trace!("{params:?}");
+
+	let server_id = lookup::server_id(&server, &state).await?;
+
+	// Streamed exports don't have to fit into memory, so they may be a lot larger.
+	let max_limit = match format {
+		Format::NdJson => 10_000,
+		_ => 500,
+	};
+
+	let mut query = QueryBuilder::new(RecordQuery::SELECT);
+
+	push_filters(&mut query, server_id, &params);
+
+	query
+		.push(" ORDER BY record.created_on DESC, record.id DESC ")
+		.push(" LIMIT ")
+		.push_bind(
+			params
+				.limit
+				.unwrap_or(100)
+				.clamp(1, max_limit),
+		)
+		.push(" OFFSET ")
+		.push_bind(params.offset.unwrap_or(0));
+
+	if format == Format::NdJson {
+		return format::stream_ndjson::<RecordQuery, _>(
+			query,
+			Record::try_from,
+			fields.fields,
+			state,
+		)
+		.await;
+	}
+
+	Ok(fetch(query, &state)
+		.await
+		.into_response())
+}
+
+async fn fetch(mut query: QueryBuilder<'_, MySql>, state: &APIState) -> Response<Vec<Record>> {
+	let records: Vec<RecordQuery> = query
+		.build_query_as()
+		.fetch_all(state.db())
+		.await?;
+
+	if records.is_empty() {
+		yeet!(Error::NoContent);
+	}
+
+	debug!("Records:\n\t{records:?}");
+
+	let records = records
+		.into_iter()
+		.map(Record::try_from)
+		.collect::<Result<Vec<_>>>()?;
+
+	let last_modified = records
+		.first()
+		.map(|record| record.created_on);
+
+	Ok(ResponseBody::from(records).last_modified(last_modified))
+}
+
+/// Narrows `records AS record` down to valid records set on the server. Starts with `WHERE`.
+fn push_filters(query: &mut QueryBuilder<'_, MySql>, server_id: u16, params: &Params) {
+	query
+		.push(r#" WHERE record.status = "valid" "#)
+		.push(" AND record.server_id = ")
+		.push_bind(server_id);
+
+	if let Some(mode) = params.mode {
+		query
+			.push(" AND record.mode_id = ")
+			.push_bind(mode as u8);
+	}
+
+	params.filter.push(query, "record");
+
+	if let Some(tickrate) = params.tickrate {
+		query
+			.push(" AND record.tickrate = ")
+			.push_bind(tickrate);
+	}
+
+	if params.wr_only.unwrap_or(false) {
+		query.push(" AND record.id IN (SELECT wr.id FROM ");
+		super::push_wrs(query, server_id, params.mode, params.filter);
+		query.push(")");
+	}
+}
@@ -1,14 +1,14 @@
 use {
-	crate::{response::Response, state::APIState},
+	crate::{fields::FieldParams, response::Response, state::APIState},
 	axum::extract::{Query, State},
 	gokz_rs::PlayerIdentifier,
 	itertools::Itertools,
 	schnose_api::{
-		error::{yeet, Error},
+		error::{yeet, Error, Result},
 		models::{Server, ServerQuery},
 	},
 	serde::Deserialize,
-	sqlx::QueryBuilder,
+	sqlx::{MySql, QueryBuilder},
 	tracing::{debug, trace},
 };
 
@@ -22,46 +22,26 @@ pub struct Params {
 #[axum::debug_handler]
 pub async fn get(
 	Query(params): Query<Params>,
+	Query(fields): Query<FieldParams>,
 	State(state): State<APIState>,
 ) -> Response<Vec<Server>> {
 	trace!("GET /api/servers");
 	trace!("{params:?}");
+	trace!("{fields:?}");
 
-	let mut query = QueryBuilder::new(
-		r#"
-		SELECT
-		  server.*,
-		  JSON_OBJECT(
-		    "name",     owner.name,
-		    "steam_id", owner.id
-		  ) AS owned_by,
-		  approver.id AS approved_by
-		FROM servers AS server
-		LEFT JOIN players AS owner ON owner.id = server.owned_by
-		LEFT JOIN players AS approver ON approver.id = server.approved_by
-		"#,
-	);
+	let servers = fetch(&params, &fields, &state).await?;
 
-	if let Some(name) = params.name {
-		query
-			.push(" WHERE server.name LIKE ")
-			.push_bind(format!("%{name}%"));
+	if servers.is_empty() {
+		yeet!(Error::NoContent);
 	}
 
-	if let Some(owner) = params.owned_by {
-		match owner {
-			PlayerIdentifier::Name(name) => {
-				query
-					.push(" WHERE owner.name LIKE ")
-					.push_bind(format!("%{name}%"));
-			}
-			PlayerIdentifier::SteamID(steam_id) => {
-				query
-					.push(" WHERE owner.id = ")
-					.push_bind(steam_id.as_id32());
-			}
-		}
-	}
+	Ok(servers.into())
+}
+
+async fn fetch(params: &Params, fields: &FieldParams, state: &APIState) -> Result<Vec<Server>> {
+	let mut query = QueryBuilder::new(select(params, fields));
+
+	push_filters(&mut query, params);
 
 	query
 		.push(" LIMIT ")
@@ -78,14 +58,69 @@ pub async fn get(
 
 	debug!("Servers:\n\t{servers:?}");
 
-	if servers.is_empty() {
-		yeet!(Error::NoContent);
-	}
-
-	let servers = servers
+	Ok(servers
 		.into_iter()
 		.map(Into::into)
-		.collect_vec();
+		.collect_vec())
+}
 
-	Ok(servers.into())
+/// Only joins in the owner if they are expanded or needed to filter by name.
+fn select(params: &Params, fields: &FieldParams) -> String {
+	let expand_owner = fields.expands("owner");
+	let join_owner = expand_owner || matches!(params.owned_by, Some(PlayerIdentifier::Name(_)));
+
+	let owned_by = if expand_owner {
+		r#"JSON_OBJECT("name", owner.name, "steam_id", owner.id)"#
+	} else {
+		r#"CAST("null" AS JSON)"#
+	};
+
+	let mut select = format!(
+		r#"
+		SELECT
+		  server.id,
+		  server.name,
+		  server.ip,
+		  server.port,
+		  server.tickrate,
+		  {owned_by} AS owned_by,
+		  JSON_OBJECT(
+		    "steam_id",  server.approved_by,
+		    "name",      approver.name,
+		    "is_banned", approver.is_banned
+		  ) AS approved_by
+		FROM servers AS server
+		LEFT JOIN players AS approver ON approver.id = server.approved_by
+		"#
+	);
+
+	if join_owner {
+		select.push_str(" LEFT JOIN players AS owner ON owner.id = server.owned_by ");
+	}
+
+	select
+}
+
+fn push_filters(query: &mut QueryBuilder<'_, MySql>, params: &Params) {
+	query.push(" WHERE TRUE ");
+
+	if let Some(name) = &params.name {
+		query
+			.push(" AND server.name LIKE ")
+			.push_bind(format!("%{name}%"));
+	}
+
+	match &params.owned_by {
+		Some(PlayerIdentifier::Name(name)) => {
+			query
+				.push(" AND owner.name LIKE ")
+				.push_bind(format!("%{name}%"));
+		}
+		Some(PlayerIdentifier::SteamID(steam_id)) => {
+			query
+				.push(" AND server.owned_by = ")
+				.push_bind(steam_id.as_id32());
+		}
+		None => {}
+	}
 }
@@ -0,0 +1,152 @@
+use {
+	crate::{lookup, response::Response, state::APIState},
+	axum::extract::{Path, Query, State},
+	gokz_rs::{Mode, ServerIdentifier},
+	schnose_api::{
+		error::{yeet, Error, Result},
+		models::{Activity, BusiestMap, RuntypeFilter, ServerStats},
+	},
+	serde::Deserialize,
+	sqlx::{MySql, QueryBuilder},
+	tracing::{debug, trace},
+};
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Params {
+	mode: Option<Mode>,
+	#[serde(flatten)]
+	filter: RuntypeFilter,
+}
+
+#[axum::debug_handler]
+pub async fn get(
+	Path(server): Path<ServerIdentifier>,
+	Query(params): Query<Params>,
+	State(state): State<APIState>,
+) -> Response<ServerStats> {
+	trace!("GET /api/servers/{server:?}/stats");
+	trace!("{params:?}");
+
+	let server_id = lookup::server_id(&server, &state).await?;
+	let stats = stats(server_id, &params, &state).await?;
+
+	debug!("Stats:\n\t{stats:?}");
+
+	Ok(stats.into())
+}
+
+async fn stats(server_id: u16, params: &Params, state: &APIState) -> Result<ServerStats> {
+	let (records, players) = totals(server_id, params, state).await?;
+
+	Ok(ServerStats {
+		server_id,
+		records,
+		wrs: wrs(server_id, params, state).await?,
+		players,
+		busiest_maps: busiest_maps(server_id, params, state).await?,
+		activity: activity(server_id, params, state).await?,
+	})
+}
+
+/// Narrows `records AS r` down to valid records set on the server. Starts with `WHERE`.
+fn push_filter(query: &mut QueryBuilder<'_, MySql>, server_id: u16, params: &Params) {
+	query
+		.push(r#" WHERE r.status = "valid" "#)
+		.push(" AND r.server_id = ")
+		.push_bind(server_id);
+
+	if let Some(mode) = params.mode {
+		query
+			.push(" AND r.mode_id = ")
+			.push_bind(mode as u8);
+	}
+
+	params.filter.push(query, "r");
+}
+
+/// The number of valid records set on the server and the number of players who set them.
+async fn totals(server_id: u16, params: &Params, state: &APIState) -> Result<(u64, u64)> {
+	let mut query =
+		QueryBuilder::new("SELECT COUNT(*), COUNT(DISTINCT r.player_id) FROM records AS r");
+
+	push_filter(&mut query, server_id, params);
+
+	let (records, players): (i64, i64) = query
+		.build_query_as()
+		.fetch_one(state.db())
+		.await?;
+
+	if records == 0 {
+		yeet!(Error::NoContent);
+	}
+
+	Ok((records as u64, players as u64))
+}
+
+/// Records set on the server that were faster than every valid run before them on the same
+/// course, mode and runtype.
+async fn wrs(server_id: u16, params: &Params, state: &APIState) -> Result<u64> {
+	let mut query = QueryBuilder::new("SELECT COUNT(*) FROM ");
+
+	super::push_wrs(&mut query, server_id, params.mode, params.filter);
+
+	let (wrs,): (i64,) = query
+		.build_query_as()
+		.fetch_one(state.db())
+		.await?;
+
+	Ok(wrs as u64)
+}
+
+async fn busiest_maps(
+	server_id: u16,
+	params: &Params,
+	state: &APIState,
+) -> Result<Vec<BusiestMap>> {
+	let mut query = QueryBuilder::new(
+		r#"
+		SELECT
+		  map.id AS map_id,
+		  map.name AS map_name,
+		  COUNT(*) AS records
+		FROM records AS r
+		JOIN courses AS course ON course.id = r.course_id
+		JOIN maps AS map ON map.id = course.map_id
+		"#,
+	);
+
+	push_filter(&mut query, server_id, params);
+
+	query
+		.push(" GROUP BY map.id ")
+		.push(" ORDER BY records DESC, map.name ")
+		.push(" LIMIT 10 ");
+
+	Ok(query
+		.build_query_as()
+		.fetch_all(state.db())
+		.await?)
+}
+
+async fn activity(server_id: u16, params: &Params, state: &APIState) -> Result<Vec<Activity>> {
+	let mut query = QueryBuilder::new(
+		r#"
+		SELECT
+		  DATE_FORMAT(r.created_on, "%Y-%m") AS month,
+		  COUNT(*) AS records,
+		  COUNT(DISTINCT r.player_id) AS players
+		FROM records AS r
+		"#,
+	);
+
+	push_filter(&mut query, server_id, params);
+
+	query
+		.push(" GROUP BY month ")
+		.push(" ORDER BY month ");
+
+	Ok(query
+		.build_query_as()
+		.fetch_all(state.db())
+		.await?)
+}
@@ -0,0 +1,35 @@
+use {
+	crate::{
+		lookup,
+		response::{Response, ResponseBody},
+		state::APIState,
+	},
+	axum::extract::{Path, State},
+	gokz_rs::ServerIdentifier,
+	schnose_api::{error::Error, models::ServerStatus},
+	tracing::{debug, trace},
+};
+
+#[axum::debug_handler]
+pub async fn get(
+	Path(server): Path<ServerIdentifier>,
+	State(state): State<APIState>,
+) -> Response<ServerStatus> {
+	trace!("GET /api/servers/{server:?}/status");
+
+	let server_id = lookup::server_id(&server, &state).await?;
+
+	let status = state
+		.server_status
+		.read()
+		.expect("Server status lock should never be poisoned.")
+		.get(&server_id)
+		.cloned()
+		.ok_or(Error::NoContent)?;
+
+	debug!("Status:\n\t{status:?}");
+
+	let last_modified = Some(status.updated_on);
+
+	Ok(ResponseBody::from(status).last_modified(last_modified))
+}
@@ -0,0 +1,54 @@
+use {
+	crate::{auth::ApiKey, response::Response, state::APIState},
+	axum::extract::{Path, Query, State},
+	schnose_api::{
+		error::{yeet, Error},
+		models::WebhookDelivery,
+	},
+	serde::Deserialize,
+	tracing::{debug, trace},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Params {
+	limit: Option<u16>,
+}
+
+#[axum::debug_handler]
+pub async fn get(
+	api_key: ApiKey,
+	Path(webhook_id): Path<u32>,
+	Query(params): Query<Params>,
+	State(state): State<APIState>,
+) -> Response<Vec<WebhookDelivery>> {
+	trace!("GET /api/webhooks/{webhook_id}/deliveries");
+	trace!("{params:?}");
+
+	let deliveries: Vec<WebhookDelivery> = sqlx::query_as(
+		r#"
+		SELECT delivery.*
+		FROM webhook_deliveries AS delivery
+		JOIN webhooks AS webhook ON webhook.id = delivery.webhook_id
+		WHERE webhook.id = ? AND webhook.api_key_id = ?
+		ORDER BY delivery.id DESC
+		LIMIT ?
+		"#,
+	)
+	.bind(webhook_id)
+	.bind(api_key.id)
+	.bind(match params.limit.unwrap_or(100) {
+		0 => 1,
+		limit @ (1..=500) => limit,
+		501.. => 500,
+	})
+	.fetch_all(state.db())
+	.await?;
+
+	debug!("Deliveries:\n\t{deliveries:?}");
+
+	if deliveries.is_empty() {
+		yeet!(Error::NoContent);
+	}
+
+	Ok(deliveries.into())
+}
@@ -0,0 +1,37 @@
+use {
+	crate::{auth::ApiKey, response::Response, state::APIState},
+	axum::extract::{Path, State},
+	schnose_api::{
+		error::Error,
+		models::{Webhook, WebhookQuery},
+	},
+	tracing::{debug, trace},
+};
+
+#[axum::debug_handler]
+pub async fn delete(
+	api_key: ApiKey,
+	Path(webhook_id): Path<u32>,
+	State(state): State<APIState>,
+) -> Response<Webhook> {
+	trace!("DELETE /api/webhooks/{webhook_id}");
+
+	let webhook: WebhookQuery =
+		sqlx::query_as("SELECT * FROM webhooks WHERE id = ? AND api_key_id = ?")
+			.bind(webhook_id)
+			.bind(api_key.id)
+			.fetch_optional(state.db())
+			.await?
+			.ok_or(Error::NoContent)?;
+
+	sqlx::query("DELETE FROM webhooks WHERE id = ?")
+		.bind(webhook_id)
+		.execute(state.db())
+		.await?;
+
+	state.webhooks.invalidate();
+
+	debug!("Deleted webhook:\n\t{webhook:?}");
+
+	Ok(Webhook::from(webhook).into())
+}
@@ -0,0 +1,5 @@
+pub mod root;
+
+pub mod ident;
+
+pub mod deliveries;
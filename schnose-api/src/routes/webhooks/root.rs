@@ -0,0 +1,116 @@
+use {
+	crate::{
+		auth::{generate_secret, ApiKey},
+		delivery,
+		response::Response,
+		state::APIState,
+	},
+	axum::{extract::State, Json},
+	itertools::Itertools,
+	schnose_api::{
+		error::{yeet, Error, Result},
+		models::{RecordFilter, Webhook, WebhookQuery},
+	},
+	serde::{Deserialize, Serialize},
+	tracing::{debug, trace},
+};
+
+/// Every webhook costs a request per matching record, so a single key cannot register an
+/// unbounded number of them.
+const MAX_WEBHOOKS_PER_KEY: i64 = 10;
+
+#[derive(Debug, Deserialize)]
+pub struct Body {
+	url: String,
+	#[serde(default)]
+	filter: RecordFilter,
+}
+
+/// The secret is only ever shown in this response. Every delivery is signed with it; see
+/// `X-Schnose-Signature`.
+#[derive(Debug, Serialize)]
+pub struct CreatedWebhook {
+	#[serde(flatten)]
+	webhook: Webhook,
+	secret: String,
+}
+
+#[axum::debug_handler]
+pub async fn get(api_key: ApiKey, State(state): State<APIState>) -> Response<Vec<Webhook>> {
+	trace!("GET /api/webhooks");
+
+	let webhooks: Vec<WebhookQuery> = sqlx::query_as("SELECT * FROM webhooks WHERE api_key_id = ?")
+		.bind(api_key.id)
+		.fetch_all(state.db())
+		.await?;
+
+	debug!("Webhooks:\n\t{webhooks:?}");
+
+	if webhooks.is_empty() {
+		yeet!(Error::NoContent);
+	}
+
+	Ok(webhooks
+		.into_iter()
+		.map(Into::into)
+		.collect_vec()
+		.into())
+}
+
+#[axum::debug_handler]
+pub async fn post(
+	api_key: ApiKey,
+	State(state): State<APIState>,
+	Json(body): Json<Body>,
+) -> Response<CreatedWebhook> {
+	trace!("POST /api/webhooks");
+	trace!("{body:?}");
+
+	check_limit(&api_key, &state).await?;
+	delivery::check_url(&body.url).await?;
+
+	let secret = generate_secret();
+
+	let webhook = insert(&api_key, &body, &secret, &state).await?;
+
+	state.webhooks.invalidate();
+
+	debug!("Webhook:\n\t{webhook:?}");
+
+	Ok(CreatedWebhook { webhook: webhook.into(), secret }.into())
+}
+
+async fn check_limit(api_key: &ApiKey, state: &APIState) -> Result<()> {
+	let (registered,): (i64,) =
+		sqlx::query_as("SELECT COUNT(*) FROM webhooks WHERE api_key_id = ?")
+			.bind(api_key.id)
+			.fetch_one(state.db())
+			.await?;
+
+	if registered >= MAX_WEBHOOKS_PER_KEY {
+		yeet!(Error::InvalidInput("An API key can register at most 10 webhooks."));
+	}
+
+	Ok(())
+}
+
+async fn insert(
+	api_key: &ApiKey,
+	body: &Body,
+	secret: &str,
+	state: &APIState,
+) -> Result<WebhookQuery> {
+	let result =
+		sqlx::query("INSERT INTO webhooks (api_key_id, url, secret, filter) VALUES (?, ?, ?, ?)")
+			.bind(api_key.id)
+			.bind(&body.url)
+			.bind(secret)
+			.bind(sqlx::types::Json(&body.filter))
+			.execute(state.db())
+			.await?;
+
+	Ok(sqlx::query_as("SELECT * FROM webhooks WHERE id = ?")
+		.bind(result.last_insert_id())
+		.fetch_one(state.db())
+		.await?)
+}
@@ -1,4 +1,9 @@
-use serde::{de, Deserialize, Deserializer};
+use {
+	gokz_rs::SteamID,
+	serde::{de, Deserialize, Deserializer, Serialize, Serializer},
+	sqlx::types::chrono::{DateTime, NaiveDate, NaiveDateTime, Utc},
+	std::{fmt::Display, str::FromStr},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 #[serde(untagged)]
@@ -32,3 +37,236 @@ where
 			de::Error::invalid_value(de::Unexpected::Unsigned(n as u64), &"bool must be 0 or 1")
 		})
 }
+
+/// Parses an optional timestamp from a query parameter. Accepts RFC 3339, `YYYY-MM-DDTHH:MM:SS`,
+/// plain dates (meaning the end of that day) and unix timestamps in seconds.
+pub fn deser_opt_timestamp<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	let Some(timestamp) = Option::<String>::deserialize(deserializer)? else {
+		return Ok(None);
+	};
+
+	if let Ok(seconds) = timestamp.parse::<i64>() {
+		return DateTime::from_timestamp(seconds, 0)
+			.map(Some)
+			.ok_or_else(|| de::Error::custom("unix timestamp out of range"));
+	}
+
+	if let Ok(datetime) = DateTime::parse_from_rfc3339(&timestamp) {
+		return Ok(Some(datetime.with_timezone(&Utc)));
+	}
+
+	if let Ok(datetime) = NaiveDateTime::parse_from_str(&timestamp, "%Y-%m-%dT%H:%M:%S") {
+		return Ok(Some(datetime.and_utc()));
+	}
+
+	NaiveDate::parse_from_str(&timestamp, "%Y-%m-%d")
+		.ok()
+		.and_then(|date| date.and_hms_opt(23, 59, 59))
+		.map(|datetime| Some(datetime.and_utc()))
+		.ok_or_else(|| de::Error::custom(format!("`{timestamp}` is not a valid timestamp")))
+}
+
+/// Parses an optional comma separated list from a query parameter, e.g. `?ids=1,2,3`.
+pub fn deser_opt_list<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+where
+	D: Deserializer<'de>,
+	T: FromStr,
+	T::Err: Display,
+{
+	let Some(list) = Option::<String>::deserialize(deserializer)? else {
+		return Ok(None);
+	};
+
+	list.split(',')
+		.map(str::trim)
+		.filter(|item| !item.is_empty())
+		.map(|item| {
+			item.parse()
+				.map_err(|err| de::Error::custom(format!("`{item}` is invalid: {err}")))
+		})
+		.collect::<Result<_, _>>()
+		.map(Some)
+}
+
+/// How SteamIDs are written in responses, picked via `?steam_id_format=`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SteamIdFormat {
+	/// `STEAM_1:1:161178172`
+	#[default]
+	Steam2,
+
+	/// `[U:1:322356345]`
+	Steam3,
+
+	/// `76561198282622073`, as a string since it doesn't fit into a JavaScript number.
+	Steam64,
+
+	/// `322356345`
+	AccountId,
+}
+
+/// How timestamps are written in responses, picked via `?time_format=`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeFormat {
+	/// `2023-04-20T13:37:00Z`
+	#[default]
+	Rfc3339,
+
+	/// Seconds since the unix epoch.
+	Unix,
+}
+
+/// Everything about a response's representation a client can pick per request.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct OutputFormat {
+	#[serde(default)]
+	pub steam_id_format: SteamIdFormat,
+
+	#[serde(default)]
+	pub time_format: TimeFormat,
+}
+
+tokio::task_local! {
+	/// The [`OutputFormat`] of the request currently being handled. Anything serialized outside
+	/// of a request, like webhook payloads, uses the default.
+	pub static OUTPUT_FORMAT: OutputFormat;
+}
+
+impl OutputFormat {
+	pub fn current() -> Self {
+		OUTPUT_FORMAT
+			.try_with(|format| *format)
+			.unwrap_or_default()
+	}
+}
+
+/// Serializes a SteamID according to [`OutputFormat::current`].
+pub fn ser_steam_id<S>(steam_id: &SteamID, serializer: S) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	match OutputFormat::current().steam_id_format {
+		SteamIdFormat::Steam2 => steam_id.serialize(serializer),
+		SteamIdFormat::Steam3 => steam_id
+			.as_community_id()
+			.serialize(serializer),
+		SteamIdFormat::Steam64 => steam_id
+			.as_id64()
+			.to_string()
+			.serialize(serializer),
+		SteamIdFormat::AccountId => steam_id.as_id32().serialize(serializer),
+	}
+}
+
+pub fn ser_opt_steam_id<S>(steam_id: &Option<SteamID>, serializer: S) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	match steam_id {
+		Some(steam_id) => ser_steam_id(steam_id, serializer),
+		None => serializer.serialize_none(),
+	}
+}
+
+/// Serializes a timestamp according to [`OutputFormat::current`].
+pub fn ser_datetime<S>(date: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	match OutputFormat::current().time_format {
+		TimeFormat::Rfc3339 => schnosedb::serialize_datetime(date, serializer),
+		TimeFormat::Unix => date.timestamp().serialize(serializer),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, serde_json::json};
+
+	fn list(value: serde_json::Value) -> Result<Option<Vec<u32>>, serde_json::Error> {
+		deser_opt_list(value)
+	}
+
+	#[test]
+	fn deser_opt_list_parses_items() {
+		assert_eq!(list(json!("1,2,3")).unwrap(), Some(vec![1, 2, 3]));
+		assert_eq!(list(json!(" 1 , 2 ")).unwrap(), Some(vec![1, 2]));
+	}
+
+	#[test]
+	fn deser_opt_list_skips_empty_items() {
+		assert_eq!(list(json!("1,,2,")).unwrap(), Some(vec![1, 2]));
+		assert_eq!(list(json!("")).unwrap(), Some(vec![]));
+	}
+
+	#[test]
+	fn deser_opt_list_missing() {
+		assert_eq!(list(json!(null)).unwrap(), None);
+	}
+
+	#[test]
+	fn deser_opt_list_rejects_invalid_items() {
+		let error = list(json!("1,two,3")).unwrap_err();
+
+		assert!(error
+			.to_string()
+			.starts_with("`two` is invalid: "));
+	}
+
+	/// Serializes with `serialize` as if handling a request that asked for `format`.
+	fn serialize_as<T>(
+		format: OutputFormat,
+		value: &T,
+		serialize: fn(&T, serde_json::value::Serializer) -> serde_json::Result<serde_json::Value>,
+	) -> serde_json::Value {
+		OUTPUT_FORMAT
+			.sync_scope(format, || serialize(value, serde_json::value::Serializer))
+			.unwrap()
+	}
+
+	fn steam_id_as(steam_id_format: SteamIdFormat) -> serde_json::Value {
+		let format = OutputFormat { steam_id_format, ..Default::default() };
+
+		serialize_as(format, &SteamID::from_id32(322356345), ser_steam_id)
+	}
+
+	#[test]
+	fn ser_steam_id_per_format() {
+		assert_eq!(steam_id_as(SteamIdFormat::Steam2), json!("STEAM_1:1:161178172"));
+		assert_eq!(steam_id_as(SteamIdFormat::Steam3), json!("[U:1:322356345]"));
+		assert_eq!(steam_id_as(SteamIdFormat::Steam64), json!("76561198282622073"));
+		assert_eq!(steam_id_as(SteamIdFormat::AccountId), json!(322356345));
+	}
+
+	#[test]
+	fn ser_opt_steam_id_none() {
+		let value = serialize_as(OutputFormat::default(), &None, ser_opt_steam_id);
+
+		assert_eq!(value, json!(null));
+	}
+
+	fn datetime_as(time_format: TimeFormat) -> serde_json::Value {
+		let format = OutputFormat { time_format, ..Default::default() };
+		let date = DateTime::from_timestamp(1682001420, 0).unwrap();
+
+		serialize_as(format, &date, ser_datetime)
+	}
+
+	#[test]
+	fn ser_datetime_per_format() {
+		assert_eq!(datetime_as(TimeFormat::Rfc3339), json!("2023-04-20T14:37:00Z"));
+		assert_eq!(datetime_as(TimeFormat::Unix), json!(1682001420));
+	}
+
+	#[test]
+	fn serializes_with_defaults_outside_of_requests() {
+		let steam_id = ser_steam_id(&SteamID::from_id32(322356345), serde_json::value::Serializer);
+
+		assert_eq!(steam_id.unwrap(), json!("STEAM_1:1:161178172"));
+	}
+}
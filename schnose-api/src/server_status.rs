@@ -0,0 +1,109 @@
+use {
+	crate::{a2s, state::APIState},
+	schnose_api::{
+		error::Result,
+		models::{OnlinePlayer, ServerStatus},
+	},
+	sqlx::types::chrono::Utc,
+	std::{collections::HashMap, io, time::Duration},
+	tracing::{debug, error, trace},
+};
+
+/// How often every known server is queried.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Servers that take longer than this to answer are considered offline.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Queries every server with a known address over A2S and replaces
+/// [`APIState::server_status`] with the results. Runs for as long as the API does.
+pub async fn poll_servers(state: APIState) {
+	let mut interval = tokio::time::interval(POLL_INTERVAL);
+
+	loop {
+		interval.tick().await;
+
+		match known_servers(&state).await {
+			Ok(servers) => refresh(servers, &state).await,
+			Err(err) => error!("Failed to fetch server addresses: {err:?}"),
+		}
+	}
+}
+
+async fn known_servers(state: &APIState) -> Result<Vec<(u16, String, u16)>> {
+	Ok(sqlx::query_as(
+		r#"
+		SELECT id, ip, port
+		FROM servers
+		WHERE ip IS NOT NULL
+		  AND port IS NOT NULL
+		"#,
+	)
+	.fetch_all(state.db())
+	.await?)
+}
+
+async fn refresh(servers: Vec<(u16, String, u16)>, state: &APIState) {
+	let queries = servers
+		.into_iter()
+		.map(|(server_id, ip, port)| tokio::spawn(query_server(server_id, ip, port)))
+		.collect::<Vec<_>>();
+
+	let mut statuses = HashMap::new();
+
+	for query in queries {
+		if let Ok(Some(status)) = query.await {
+			statuses.insert(status.server_id, status);
+		}
+	}
+
+	debug!("{} servers online.", statuses.len());
+
+	*state
+		.server_status
+		.write()
+		.expect("Server status lock should never be poisoned.") = statuses;
+}
+
+/// `None` if the server is offline or doesn't speak A2S.
+async fn query_server(server_id: u16, ip: String, port: u16) -> Option<ServerStatus> {
+	match try_query_server(server_id, &ip, port).await {
+		Ok(status) => Some(status),
+		Err(err) => {
+			trace!("Server #{server_id} ({ip}:{port}) did not respond: {err}");
+			None
+		}
+	}
+}
+
+async fn try_query_server(server_id: u16, ip: &str, port: u16) -> io::Result<ServerStatus> {
+	let addr = tokio::net::lookup_host((ip, port))
+		.await?
+		.next()
+		.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "address did not resolve"))?;
+
+	let info = a2s::info(addr, QUERY_TIMEOUT).await?;
+
+	// Some servers hide their player list; that shouldn't make them count as offline.
+	let players = a2s::players(addr, QUERY_TIMEOUT)
+		.await
+		.unwrap_or_default();
+
+	Ok(ServerStatus {
+		server_id,
+		name: info.name,
+		map: info.map,
+		player_count: info.players,
+		max_players: info.max_players,
+		bots: info.bots,
+		players: players
+			.into_iter()
+			.map(|player| OnlinePlayer {
+				name: player.name,
+				score: player.score,
+				duration: player.duration,
+			})
+			.collect(),
+		updated_on: Utc::now(),
+	})
+}
@@ -1,14 +1,31 @@
 use {
-	crate::routes,
-	axum::{routing::get, Router, Server, ServiceExt},
+	crate::{
+		cache::Cache, conditional, delivery, difficulty, feed, fields, format, routes,
+		server_status,
+	},
+	axum::{
+		middleware,
+		routing::{delete, get, post, put},
+		Router, Server, ServiceExt,
+	},
+	schnose_api::models::{RecordEvent, ServerStatus},
 	shuttle_runtime::async_trait,
 	sqlx::{mysql::MySqlPoolOptions, MySql, Pool},
-	std::{net::SocketAddr, sync::Arc},
+	std::{
+		collections::HashMap,
+		net::SocketAddr,
+		sync::{Arc, RwLock},
+		time::Duration,
+	},
+	tokio::sync::broadcast,
 	tower::Layer,
 	tower_http::normalize_path::NormalizePathLayer,
-	tracing::{error, info},
+	tracing::{error, info, warn},
 };
 
+/// Maps and modes only change a few times a day, and writes invalidate the cache anyway.
+const CACHE_TTL: Duration = Duration::from_secs(60 * 10);
+
 pub type ShuttleResult = Result<APIState, shuttle_service::Error>;
 
 #[async_trait]
@@ -18,17 +35,65 @@ impl shuttle_service::Service for APIState {
 
 		info!("Listening on {addr}.");
 
-		let router = Router::new()
+		tokio::spawn(feed::poll_new_records(self.clone()));
+		tokio::spawn(delivery::deliver_record_events(self.clone()));
+		tokio::spawn(difficulty::recompute_difficulty(self.clone()));
+		tokio::spawn(server_status::poll_servers(self.clone()));
+
+		let mut router = Router::new()
 			.route("/health", get(|| async { "(͡ ͡° ͜ つ ͡͡°)" }))
 			.route("/api/modes", get(routes::modes::root::get))
 			.route("/api/modes/:ident", get(routes::modes::ident::get))
 			.route("/api/players", get(routes::players::root::get))
+			.route("/api/players/compare", get(routes::players::compare::get))
+			.route("/api/players/batch", post(routes::players::batch::post))
 			.route("/api/players/:ident", get(routes::players::ident::get))
+			.route("/api/players/:ident/history", get(routes::players::history::get))
+			.route("/api/players/:ident/names", get(routes::players::names::get))
+			.route(
+				"/api/players/:ident/recommendations",
+				get(routes::players::recommendations::get),
+			)
 			.route("/api/maps", get(routes::maps::root::get))
+			.route("/api/maps/batch", post(routes::maps::batch::post))
 			.route("/api/maps/:ident", get(routes::maps::ident::get))
+			.route("/api/maps/:ident/leaderboard", get(routes::maps::leaderboard::get))
+			.route("/api/maps/:ident/wr", get(routes::maps::wr::get))
+			.route("/api/maps/:ident/wr-history", get(routes::maps::wr_history::get))
+			.route("/api/courses/outliers", get(routes::courses::outliers::get))
+			.route("/api/courses/:id/stats", get(routes::courses::stats::get))
 			.route("/api/servers", get(routes::servers::root::get))
+			.route("/api/servers/online", get(routes::servers::online::get))
 			.route("/api/servers/:ident", get(routes::servers::ident::get))
+			.route("/api/servers/:ident/records", get(routes::servers::records::get))
+			.route("/api/servers/:ident/stats", get(routes::servers::stats::get))
+			.route("/api/servers/:ident/status", get(routes::servers::status::get))
 			.route("/api/records", get(routes::records::root::get))
+			.route("/api/records/batch", post(routes::records::batch::post))
+			.route("/api/records/stream", get(routes::records::stream::get))
+			.route("/api/hall-of-fame/wrs", get(routes::hall_of_fame::wrs::get))
+			.route("/api/hall-of-fame/completions", get(routes::hall_of_fame::completions::get))
+			.route(
+				"/api/webhooks",
+				get(routes::webhooks::root::get).post(routes::webhooks::root::post),
+			)
+			.route("/api/webhooks/:id", delete(routes::webhooks::ident::delete))
+			.route("/api/webhooks/:id/deliveries", get(routes::webhooks::deliveries::get));
+
+		if self.admin_key.is_some() {
+			router = router
+				.route("/api/records/:id/status", put(routes::records::status::put))
+				.route("/api/cache", delete(routes::cache::root::delete))
+				.route("/api/keys", post(routes::keys::root::post));
+		} else {
+			warn!("No `ADMIN_KEY` secret set. Admin routes are disabled.");
+		}
+
+		let router = router
+			.layer(middleware::from_fn(format::scope_output_format))
+			.layer(middleware::from_fn(fields::select_fields))
+			.layer(middleware::from_fn(format::negotiate_format))
+			.layer(middleware::from_fn(conditional::conditional_get))
 			.with_state(self);
 
 		let router = NormalizePathLayer::trim_trailing_slash().layer(router);
@@ -46,11 +111,18 @@ impl shuttle_service::Service for APIState {
 #[derive(Debug, Clone)]
 pub struct APIState {
 	pub database_connection: Arc<Pool<MySql>>,
+	pub admin_key: Option<Arc<str>>,
+	pub cache: Arc<Cache>,
+	pub record_events: broadcast::Sender<RecordEvent>,
+	pub webhooks: Arc<delivery::Webhooks>,
+
+	/// The latest A2S response of every server that is currently online, keyed by server ID.
+	pub server_status: Arc<RwLock<HashMap<u16, ServerStatus>>>,
 }
 
 impl APIState {
-	#[tracing::instrument(skip(connection_string))]
-	pub async fn new(connection_string: &str) -> Self {
+	#[tracing::instrument(skip(connection_string, admin_key))]
+	pub async fn new(connection_string: &str, admin_key: Option<String>) -> Self {
 		let database_connection = MySqlPoolOptions::new()
 			.connect(connection_string)
 			.await
@@ -58,6 +130,11 @@ impl APIState {
 
 		Self {
 			database_connection: Arc::new(database_connection),
+			admin_key: admin_key.map(Into::into),
+			cache: Arc::new(Cache::new(CACHE_TTL)),
+			record_events: broadcast::channel(1024).0,
+			webhooks: Arc::default(),
+			server_status: Arc::new(RwLock::new(HashMap::new())),
 		}
 	}
 
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS course_difficulty (
+	course_id      INT     UNSIGNED NOT NULL,
+	mode_id        TINYINT UNSIGNED NOT NULL,
+	players        INT     UNSIGNED NOT NULL,
+	pro_ratio      DOUBLE           NOT NULL,
+	player_skill   DOUBLE           NOT NULL,
+	score          DOUBLE           NOT NULL,
+	estimated_tier TINYINT UNSIGNED NOT NULL,
+	updated_on     TIMESTAMP        NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+	PRIMARY KEY (course_id, mode_id)
+);
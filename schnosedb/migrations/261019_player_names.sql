@@ -0,0 +1,11 @@
+-- Every name a player has been seen with.
+CREATE TABLE IF NOT EXISTS player_names (
+	-- REFERENCES players (id)
+	player_id  INT          UNSIGNED NOT NULL,
+	name       VARCHAR(255)          NOT NULL,
+	first_seen TIMESTAMP             NOT NULL,
+	last_seen  TIMESTAMP             NOT NULL,
+
+	PRIMARY KEY (player_id, name),
+	INDEX player_names_name (name)
+);
@@ -0,0 +1 @@
+CREATE INDEX records_runtype ON records (course_id, mode_id, (teleports > 0), time);
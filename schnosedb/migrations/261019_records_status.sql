@@ -0,0 +1,4 @@
+ALTER TABLE records
+  ADD COLUMN status        ENUM("valid", "under_review", "invalidated") NOT NULL DEFAULT "valid",
+  ADD COLUMN status_reason VARCHAR(255),
+  ADD COLUMN moderated_by  INT UNSIGNED;
@@ -0,0 +1,2 @@
+ALTER TABLE records
+  ADD COLUMN tickrate TINYINT UNSIGNED AFTER teleports;
@@ -0,0 +1,4 @@
+ALTER TABLE servers
+  ADD COLUMN ip       VARCHAR(255),
+  ADD COLUMN port     SMALLINT UNSIGNED,
+  ADD COLUMN tickrate TINYINT UNSIGNED;
@@ -0,0 +1,32 @@
+CREATE TABLE IF NOT EXISTS api_keys (
+	id         INT          UNSIGNED NOT NULL AUTO_INCREMENT,
+	name       VARCHAR(255)          NOT NULL,
+	key_hash   CHAR(64)              NOT NULL,
+	created_on TIMESTAMP             NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+	PRIMARY KEY (id),
+	UNIQUE (key_hash)
+);
+
+CREATE TABLE IF NOT EXISTS webhooks (
+	id         INT           UNSIGNED NOT NULL AUTO_INCREMENT,
+	api_key_id INT           UNSIGNED NOT NULL,
+	url        VARCHAR(2048)          NOT NULL,
+	secret     VARCHAR(64)            NOT NULL,
+	filter     JSON                   NOT NULL,
+	created_on TIMESTAMP              NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+	PRIMARY KEY (id)
+);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+	id          INT          UNSIGNED NOT NULL AUTO_INCREMENT,
+	webhook_id  INT          UNSIGNED NOT NULL,
+	record_id   INT          UNSIGNED NOT NULL,
+	attempt     TINYINT      UNSIGNED NOT NULL,
+	status_code SMALLINT     UNSIGNED,
+	error       VARCHAR(255),
+	created_on  TIMESTAMP             NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+	PRIMARY KEY (id)
+);
@@ -1,8 +1,13 @@
 DROP TABLE modes;
 DROP TABLE players;
+DROP TABLE player_names;
 DROP TABLE maps;
 DROP TABLE mappers;
 DROP TABLE courses;
 DROP TABLE filters;
 DROP TABLE servers;
 DROP TABLE records;
+DROP TABLE api_keys;
+DROP TABLE webhooks;
+DROP TABLE webhook_deliveries;
+DROP TABLE course_difficulty;
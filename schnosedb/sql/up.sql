@@ -15,6 +15,18 @@ CREATE TABLE IF NOT EXISTS players (
 	PRIMARY KEY (id)
 );
 
+-- Every name a player has been seen with.
+CREATE TABLE IF NOT EXISTS player_names (
+	-- REFERENCES players (id)
+	player_id  INT          UNSIGNED NOT NULL,
+	name       VARCHAR(255)          NOT NULL,
+	first_seen TIMESTAMP             NOT NULL,
+	last_seen  TIMESTAMP             NOT NULL,
+
+	PRIMARY KEY (player_id, name),
+	INDEX player_names_name (name)
+);
+
 CREATE TABLE IF NOT EXISTS maps (
 	id          SMALLINT     UNSIGNED NOT NULL,
 	name        VARCHAR(255)          NOT NULL,
@@ -63,6 +75,9 @@ CREATE TABLE IF NOT EXISTS filters (
 CREATE TABLE IF NOT EXISTS servers (
 	id          SMALLINT     UNSIGNED NOT NULL,
 	name        VARCHAR(255)          NOT NULL,
+	ip          VARCHAR(255),
+	port        SMALLINT     UNSIGNED,
+	tickrate    TINYINT      UNSIGNED,
 	-- REFERENCES players (id)
 	owned_by    INT          UNSIGNED NOT NULL,
 	-- REFERENCES players (id)
@@ -72,18 +87,81 @@ CREATE TABLE IF NOT EXISTS servers (
 );
 
 CREATE TABLE IF NOT EXISTS records (
-	id         INT      UNSIGNED NOT NULL,
+	id            INT      UNSIGNED NOT NULL,
 	-- REFERENCES courses (id)
-	course_id  INT      UNSIGNED NOT NULL,
+	course_id     INT      UNSIGNED NOT NULL,
 	-- REFERENCES modes (id)
-	mode_id    TINYINT  UNSIGNED NOT NULL,
+	mode_id       TINYINT  UNSIGNED NOT NULL,
 	-- REFERENCES players (id)
-	player_id  INT      UNSIGNED NOT NULL,
+	player_id     INT      UNSIGNED NOT NULL,
 	-- REFERENCES servers (id)
-	server_id  SMALLINT UNSIGNED NOT NULL,
-	time       DOUBLE            NOT NULL,
-	teleports  SMALLINT UNSIGNED NOT NULL,
-	created_on TIMESTAMP         NOT NULL,
+	server_id     SMALLINT UNSIGNED NOT NULL,
+	time          DOUBLE            NOT NULL,
+	teleports     SMALLINT UNSIGNED NOT NULL,
+	tickrate      TINYINT  UNSIGNED,
+	created_on    TIMESTAMP         NOT NULL,
+	status        ENUM("valid", "under_review", "invalidated") NOT NULL DEFAULT "valid",
+	status_reason VARCHAR(255),
+	-- REFERENCES players (id)
+	moderated_by  INT      UNSIGNED,
+
+	PRIMARY KEY (id),
+	-- Leaderboards are split by runtype, i.e. whether `teleports > 0`.
+	INDEX records_runtype (course_id, mode_id, (teleports > 0), time)
+);
+
+CREATE TABLE IF NOT EXISTS api_keys (
+	id         INT          UNSIGNED NOT NULL AUTO_INCREMENT,
+	name       VARCHAR(255)          NOT NULL,
+	-- SHA-256 of the key, we never store the key itself
+	key_hash   CHAR(64)              NOT NULL,
+	created_on TIMESTAMP             NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+	PRIMARY KEY (id),
+	UNIQUE (key_hash)
+);
+
+CREATE TABLE IF NOT EXISTS webhooks (
+	id         INT           UNSIGNED NOT NULL AUTO_INCREMENT,
+	-- REFERENCES api_keys (id)
+	api_key_id INT           UNSIGNED NOT NULL,
+	url        VARCHAR(2048)          NOT NULL,
+	-- used to sign payloads (HMAC-SHA256)
+	secret     VARCHAR(64)            NOT NULL,
+	filter     JSON                   NOT NULL,
+	created_on TIMESTAMP              NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+	PRIMARY KEY (id)
+);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+	id          INT          UNSIGNED NOT NULL AUTO_INCREMENT,
+	-- REFERENCES webhooks (id)
+	webhook_id  INT          UNSIGNED NOT NULL,
+	-- REFERENCES records (id)
+	record_id   INT          UNSIGNED NOT NULL,
+	attempt     TINYINT      UNSIGNED NOT NULL,
+	status_code SMALLINT     UNSIGNED,
+	error       VARCHAR(255),
+	created_on  TIMESTAMP             NOT NULL DEFAULT CURRENT_TIMESTAMP,
 
 	PRIMARY KEY (id)
 );
+
+CREATE TABLE IF NOT EXISTS course_difficulty (
+	-- REFERENCES courses (id)
+	course_id      INT     UNSIGNED NOT NULL,
+	-- REFERENCES modes (id)
+	mode_id        TINYINT UNSIGNED NOT NULL,
+	players        INT     UNSIGNED NOT NULL,
+	-- share of players with a Pro run
+	pro_ratio      DOUBLE           NOT NULL,
+	-- average number of courses completed by the players who completed this one
+	player_skill   DOUBLE           NOT NULL,
+	-- 0.0 (easiest) to 1.0 (hardest) among all courses of this mode
+	score          DOUBLE           NOT NULL,
+	estimated_tier TINYINT UNSIGNED NOT NULL,
+	updated_on     TIMESTAMP        NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+	PRIMARY KEY (course_id, mode_id)
+);
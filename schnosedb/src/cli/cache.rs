@@ -0,0 +1,31 @@
+use {
+	crate::cli::config::Config,
+	color_eyre::{
+		eyre::{bail as yeet, Context},
+		Result,
+	},
+	tracing::info,
+};
+
+/// Tells a running SchnoseAPI instance to drop its response cache, if one is configured.
+#[tracing::instrument(skip(config))]
+pub async fn invalidate_api_cache(config: &Config) -> Result<()> {
+	let (Some(api_url), Some(admin_key)) = (&config.api_url, &config.admin_key) else {
+		return Ok(());
+	};
+
+	let response = reqwest::Client::new()
+		.delete(format!("{}/api/cache", api_url.trim_end_matches('/')))
+		.bearer_auth(admin_key)
+		.send()
+		.await
+		.context("Failed to reach SchnoseAPI.")?;
+
+	if !response.status().is_success() {
+		yeet!("Failed to invalidate SchnoseAPI cache: {}", response.status());
+	}
+
+	info!("Invalidated SchnoseAPI cache.");
+
+	Ok(())
+}
@@ -10,6 +10,12 @@ use {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
 	pub connection_string: String,
+
+	/// Base URL of a running SchnoseAPI instance whose cache should be invalidated after inserts.
+	pub api_url: Option<String>,
+
+	/// `ADMIN_KEY` of that instance.
+	pub admin_key: Option<String>,
 }
 
 pub fn get_config(config_path: &Path) -> Result<Config> {
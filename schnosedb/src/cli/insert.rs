@@ -4,7 +4,7 @@ use {
 		Result,
 	},
 	schnosedb::models::*,
-	sqlx::{query_builder::Separated, MySql, Pool, QueryBuilder},
+	sqlx::{query_builder::Separated, types::chrono::Utc, MySql, Pool, QueryBuilder},
 	tracing::info,
 };
 
@@ -32,6 +32,40 @@ where
 	Ok(())
 }
 
+/// Like [`insert_rows`], but for `player_names`. Names that are already known only widen their
+/// `first_seen` / `last_seen` range.
+pub async fn upsert_player_names(
+	database_connection: &Pool<MySql>,
+	names: &[PlayerNameRow],
+) -> Result<()> {
+	let mut query =
+		QueryBuilder::new("INSERT INTO player_names (player_id, name, first_seen, last_seen) ");
+
+	query.push_values(names, |mut query, name| {
+		query
+			.push_bind(name.player_id)
+			.push_bind(&name.name)
+			.push_bind(name.first_seen)
+			.push_bind(name.last_seen);
+	});
+
+	query.push(
+		r#"
+		ON DUPLICATE KEY UPDATE
+		  first_seen = LEAST(first_seen, VALUES(first_seen)),
+		  last_seen = GREATEST(last_seen, VALUES(last_seen))
+		"#,
+	);
+
+	query
+		.build()
+		.execute(database_connection)
+		.await
+		.context("Failed to upsert player names.")?;
+
+	Ok(())
+}
+
 pub async fn insert_rows_from_json(
 	json: String,
 	table: &str,
@@ -80,6 +114,30 @@ pub async fn insert_rows_from_json(
 			.await?;
 
 			info!("Inserted {total} rows into `{table}`.", total = players.len());
+
+			// The GlobalAPI only knows a player's current name.
+			let now = Utc::now();
+			let names = players
+				.into_iter()
+				.map(|player| PlayerNameRow {
+					player_id: player.id,
+					name: player.name,
+					first_seen: now,
+					last_seen: now,
+				})
+				.collect::<Vec<_>>();
+
+			upsert_player_names(database_connection, &names).await?;
+
+			info!("Recorded {total} names in `player_names`.", total = names.len());
+		}
+		"player_names" => {
+			let names: Vec<PlayerNameRow> =
+				serde_json::from_str(&json).context("Failed to deserialize rows.")?;
+
+			upsert_player_names(database_connection, &names).await?;
+
+			info!("Inserted {total} rows into `{table}`.", total = names.len());
 		}
 		"maps" => {
 			let maps: Vec<MapRow> =
@@ -88,7 +146,7 @@ pub async fn insert_rows_from_json(
 			insert_rows(
 				table,
 				database_connection,
-				"id, name, global, filesize, approved_by, created_on, updated_on",
+				"id, name, global, filesize, approved_by, workshop_id, created_on, updated_on",
 				&maps,
 				|mut query, map| {
 					query
@@ -97,6 +155,7 @@ pub async fn insert_rows_from_json(
 						.push_bind(map.global as u8)
 						.push_bind(map.filesize)
 						.push_bind(map.approved_by)
+						.push_bind(map.workshop_id)
 						.push_bind(map.created_on)
 						.push_bind(map.updated_on);
 				},
@@ -171,12 +230,15 @@ pub async fn insert_rows_from_json(
 			insert_rows(
 				table,
 				database_connection,
-				"id, name, owned_by, approved_by",
+				"id, name, ip, port, tickrate, owned_by, approved_by",
 				&servers,
 				|mut query, server| {
 					query
 						.push_bind(server.id)
 						.push_bind(&server.name)
+						.push_bind(&server.ip)
+						.push_bind(server.port)
+						.push_bind(server.tickrate)
 						.push_bind(server.owned_by)
 						.push_bind(server.approved_by);
 				},
@@ -192,7 +254,8 @@ pub async fn insert_rows_from_json(
 			insert_rows(
 				table,
 				database_connection,
-				"id, course_id, mode_id, player_id, server_id, time, teleports, created_on",
+				"id, course_id, mode_id, player_id, server_id, time, teleports, tickrate, created_on, \
+				 status, status_reason, moderated_by",
 				&records,
 				|mut query, record| {
 					query
@@ -203,7 +266,11 @@ pub async fn insert_rows_from_json(
 						.push_bind(record.server_id)
 						.push_bind(record.time)
 						.push_bind(record.teleports)
-						.push_bind(record.created_on);
+						.push_bind(record.tickrate)
+						.push_bind(record.created_on)
+						.push_bind(record.status)
+						.push_bind(&record.status_reason)
+						.push_bind(record.moderated_by);
 				},
 			)
 			.await?;
@@ -1,5 +1,6 @@
 pub mod args;
 pub mod config;
 
+pub mod cache;
 pub mod insert;
 pub mod select;
@@ -1,5 +1,8 @@
 use {
-	crate::cli::{args::SqlAction, insert::insert_rows_from_json, select::select_rows},
+	crate::cli::{
+		args::SqlAction, cache::invalidate_api_cache, insert::insert_rows_from_json,
+		select::select_rows,
+	},
 	clap::Parser,
 	color_eyre::{
 		eyre::{bail as yeet, Context},
@@ -32,11 +35,16 @@ async fn main() -> Result<()> {
 		.await
 		.context("Failed to connect to database.")?;
 
+	let inserted_rows = matches!(args.sql_action, SqlAction::Insert { .. });
+
 	match args.sql_action {
 		SqlAction::Select { table, limit } => {
 			let json = match table.as_str() {
 				"modes" => select_rows::<ModeRow>(&table, limit, &database_connection).await,
 				"players" => select_rows::<PlayerRow>(&table, limit, &database_connection).await,
+				"player_names" => {
+					select_rows::<PlayerNameRow>(&table, limit, &database_connection).await
+				}
 				"maps" => select_rows::<MapRow>(&table, limit, &database_connection).await,
 				"mappers" => select_rows::<MapperRow>(&table, limit, &database_connection).await,
 				"courses" => select_rows::<CourseRow>(&table, limit, &database_connection).await,
@@ -70,5 +78,9 @@ async fn main() -> Result<()> {
 		}
 	};
 
+	if inserted_rows {
+		invalidate_api_cache(&config).await?;
+	}
+
 	Ok(())
 }
@@ -2,7 +2,7 @@ mod modes;
 pub use modes::ModeRow;
 
 mod players;
-pub use players::PlayerRow;
+pub use players::{PlayerNameRow, PlayerRow};
 
 mod maps;
 pub use maps::MapRow;
@@ -20,4 +20,4 @@ mod servers;
 pub use servers::ServerRow;
 
 mod records;
-pub use records::RecordRow;
+pub use records::{RecordRow, RecordStatus};
@@ -1,6 +1,27 @@
+use sqlx::types::chrono::{DateTime, Utc};
+
 #[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow, serde::Serialize, serde::Deserialize)]
 pub struct PlayerRow {
 	pub id: u32,
 	pub name: String,
 	pub is_banned: bool,
 }
+
+/// A name `player_id` has been seen with, and when.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow, serde::Serialize, serde::Deserialize)]
+pub struct PlayerNameRow {
+	pub player_id: u32,
+	pub name: String,
+
+	#[serde(
+		serialize_with = "crate::serialize_datetime",
+		deserialize_with = "crate::deserialize_datetime"
+	)]
+	pub first_seen: DateTime<Utc>,
+
+	#[serde(
+		serialize_with = "crate::serialize_datetime",
+		deserialize_with = "crate::deserialize_datetime"
+	)]
+	pub last_seen: DateTime<Utc>,
+}
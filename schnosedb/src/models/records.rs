@@ -10,9 +10,42 @@ pub struct RecordRow {
 	pub time: f64,
 	pub teleports: u16,
 
+	/// `None` for records imported before the tickrate was tracked.
+	#[serde(default)]
+	pub tickrate: Option<u8>,
+
 	#[serde(
 		serialize_with = "crate::serialize_datetime",
 		deserialize_with = "crate::deserialize_datetime"
 	)]
 	pub created_on: DateTime<Utc>,
+
+	#[serde(default)]
+	pub status: RecordStatus,
+	#[serde(default)]
+	pub status_reason: Option<String>,
+	#[serde(default)]
+	pub moderated_by: Option<u32>,
+}
+
+/// Moderation state of a record. Only `Valid` records should ever show up on leaderboards.
+#[derive(
+	Debug,
+	Default,
+	Clone,
+	Copy,
+	PartialEq,
+	Eq,
+	Hash,
+	sqlx::Type,
+	serde::Serialize,
+	serde::Deserialize,
+)]
+#[sqlx(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum RecordStatus {
+	#[default]
+	Valid,
+	UnderReview,
+	Invalidated,
 }
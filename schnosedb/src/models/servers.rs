@@ -2,6 +2,9 @@
 pub struct ServerRow {
 	pub id: u16,
 	pub name: String,
+	pub ip: Option<String>,
+	pub port: Option<u16>,
+	pub tickrate: Option<u8>,
 	pub owned_by: u32,
 	pub approved_by: u32,
 }
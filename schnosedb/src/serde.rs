@@ -3,21 +3,67 @@ use {
 	sqlx::types::chrono::{DateTime, NaiveDateTime, Utc},
 };
 
+/// RFC 3339 with a `Z` suffix, e.g. `2023-04-20T13:37:00Z`.
 pub fn serialize_datetime<S>(date: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
 where
 	S: Serializer,
 {
-	date.format("%Y-%m-%dT%H:%M:%S")
+	date.format("%Y-%m-%dT%H:%M:%SZ")
 		.to_string()
 		.serialize(serializer)
 }
 
+/// Accepts RFC 3339 and the older `%Y-%m-%dT%H:%M:%S` without a timezone, which is always UTC.
 pub fn deserialize_datetime<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
 where
 	D: Deserializer<'de>,
 {
 	let date = String::deserialize(deserializer)?;
+
+	if let Ok(datetime) = DateTime::parse_from_rfc3339(&date) {
+		return Ok(datetime.with_timezone(&Utc));
+	}
+
 	NaiveDateTime::parse_from_str(&date, "%Y-%m-%dT%H:%M:%S")
-		.map(|datetime| DateTime::<Utc>::from_utc(datetime, Utc))
+		.map(|datetime| datetime.and_utc())
 		.map_err(|err| de::Error::custom(err.to_string()))
 }
+
+#[cfg(test)]
+mod tests {
+	use {super::*, serde_json::json};
+
+	fn deserialize(date: &str) -> Result<DateTime<Utc>, serde_json::Error> {
+		deserialize_datetime(json!(date))
+	}
+
+	#[test]
+	fn deserialize_rfc3339() {
+		let expected = DateTime::from_timestamp(1682001420, 0).unwrap();
+
+		assert_eq!(deserialize("2023-04-20T14:37:00Z").unwrap(), expected);
+		assert_eq!(deserialize("2023-04-20T16:37:00+02:00").unwrap(), expected);
+	}
+
+	#[test]
+	fn deserialize_old_format() {
+		let expected = DateTime::from_timestamp(1682001420, 0).unwrap();
+
+		assert_eq!(deserialize("2023-04-20T14:37:00").unwrap(), expected);
+	}
+
+	#[test]
+	fn deserialize_invalid() {
+		assert!(deserialize("2023-04-20").is_err());
+		assert!(deserialize("yesterday").is_err());
+	}
+
+	#[test]
+	fn round_trip() {
+		let date = DateTime::from_timestamp(1682001420, 0).unwrap();
+		let serialized = serialize_datetime(&date, serde_json::value::Serializer).unwrap();
+
+		assert_eq!(serialized, json!("2023-04-20T14:37:00Z"));
+		assert_eq!(deserialize_datetime(serialized).unwrap(), date);
+	}
+}